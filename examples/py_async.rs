@@ -0,0 +1,31 @@
+// webrust/examples/py_async.rs
+use std::time::Duration;
+use webrust::async_gui::{input_async, GuiHandle};
+use webrust::gui::GuiConfig;
+use webrust::print::println_str;
+
+#[tokio::main]
+async fn main() {
+    let gui = GuiHandle::start(GuiConfig::new().apply_env()).expect("Failed to start server");
+
+    println_str("@(blue, bold)⏱️ webrust on tokio");
+    println_str("@(gray, italic)A background task keeps printing while you answer.");
+
+    // Tâche de fond : elle continue pendant que l'on attend la saisie
+    let ticker = tokio::spawn(async {
+        for tick in 1..=5 {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            println_str(format!("@(yellow)tick {}", tick));
+        }
+    });
+
+    let name: String = input_async("Your name:").await;
+    let count: u32 = input_async("How many greetings?").await;
+    for index in 1..=count {
+        println_str(format!("@(green){}. Hello, @(bold){}@(reset)!", index, name));
+    }
+
+    let _ = ticker.await;
+    println_str("@(cyan)All tasks done.");
+    gui.finish(()).await;
+}
@@ -3,6 +3,7 @@ use webrust::prelude::*;
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 struct Person {
     name: String,
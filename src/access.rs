@@ -0,0 +1,246 @@
+//! # Access Module - Keeping the Session Private
+//!
+//! The web server answers on a local port that every page open in the
+//! browser, and every process on the machine, can reach. This module makes
+//! sure only the tab opened by webrust drives the program.
+//!
+//! ## Access Token
+//!
+//! Each run draws a random token and opens `http://127.0.0.1:8080/?token=...`.
+//! Loading the page with the token sets a `SameSite=Strict` cookie, and the
+//! token is then removed from the address bar. Every `/api/*` request must
+//! carry the token, as this cookie, as an `X-Webrust-Token` header or as a
+//! `token` query parameter:
+//!
+//! ```bash
+//! curl -H "X-Webrust-Token: $TOKEN" http://127.0.0.1:8080/api/state
+//! ```
+//!
+//! Other tabs of the same browser share the cookie; another browser needs
+//! the full URL printed at startup. A fixed token can be chosen with
+//! [`GuiConfig::token`](crate::gui::GuiConfig::token) or `WEBRUST_TOKEN`,
+//! e.g. for scripts driving a session.
+//!
+//! ## Host and Origin
+//!
+//! A server bound to a loopback address only answers requests whose `Host`
+//! is `127.0.0.1`, `localhost` or `[::1]` with its port, which defeats DNS
+//! rebinding. A request with an `Origin` header is refused unless the origin
+//! is the server itself, so other sites cannot post inputs even without
+//! reading the answer.
+//!
+//! ## Read-only Link
+//!
+//! In LAN mode (see [`crate::lan`]) a second token is drawn for a read-only
+//! link. Its holders receive the output and the state of the inputs but
+//! every `POST` they send is refused, so they can neither answer nor close
+//! the session. The server then listens beyond loopback and accepts any
+//! `Host`; the tokens are what keeps the session private. Over HTTPS the
+//! cookie is marked `Secure`.
+
+use std::sync::RwLock;
+use tiny_http::{Header, Request};
+
+/// What a request may do, from the token it carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Level {
+    /// Opened from the main link: may answer inputs and close the session.
+    Owner,
+    /// Opened from the read-only link: may only watch.
+    Viewer,
+}
+
+struct Access {
+    token: String,
+    /// Token of the read-only link, in LAN mode.
+    view_token: Option<String>,
+    cookie: String,
+    /// Whether the server uses HTTPS, so the cookie never travels in clear.
+    secure: bool,
+    /// `Host` values accepted, or `None` when the server listens beyond loopback.
+    hosts: Option<Vec<String>>,
+}
+
+impl Access {
+    fn level(&self, given: &str) -> Option<Level> {
+        if same(given, &self.token) {
+            Some(Level::Owner)
+        } else if self.view_token.as_deref().is_some_and(|view_token| same(given, view_token)) {
+            Some(Level::Viewer)
+        } else {
+            None
+        }
+    }
+
+    fn token(&self, level: Level) -> Option<&str> {
+        match level {
+            Level::Owner => Some(&self.token),
+            Level::Viewer => self.view_token.as_deref(),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ACCESS: RwLock<Option<Access>> = RwLock::new(None);
+}
+
+/// A random token of 32 hex digits: 16 bytes from the random generator of
+/// the operating system.
+pub(crate) fn generate_token() -> String {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes).expect("the system random generator is unavailable");
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Protects the server listening on `port` with `token`, plus `view_token`
+/// for the read-only link.
+pub(crate) fn configure(token: String, view_token: Option<String>, port: u16, loopback: bool, secure: bool) {
+    let hosts = loopback.then(|| {
+        ["127.0.0.1", "localhost", "[::1]"].iter().map(|host| format!("{}:{}", host, port)).collect()
+    });
+    let cookie = format!("webrust_{}", port);
+    *ACCESS.write().unwrap() = Some(Access { token, view_token, cookie, secure, hosts });
+}
+
+fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+    request.headers().iter().find(|header| header.field.equiv(name)).map(|header| header.value.as_str())
+}
+
+/// Whether `Host` names this server and `Origin`, when present, is the server itself.
+pub(crate) fn allowed_origin(request: &Request) -> bool {
+    let access = ACCESS.read().unwrap();
+    let Some(access) = access.as_ref() else {
+        return true;
+    };
+    let host = header(request, "Host").unwrap_or("").trim().to_ascii_lowercase();
+    if let Some(hosts) = &access.hosts {
+        if !hosts.contains(&host) {
+            return false;
+        }
+    }
+    match header(request, "Origin") {
+        None => true,
+        Some(origin) => {
+            let origin = origin.trim().to_ascii_lowercase();
+            origin == format!("http://{}", host) || origin == format!("https://{}", host)
+        }
+    }
+}
+
+/// Level granted by the token the request carries (cookie, header or `token` in `query`).
+pub(crate) fn authorized(request: &Request, query: &str) -> Option<Level> {
+    let access = ACCESS.read().unwrap();
+    let Some(access) = access.as_ref() else {
+        return Some(Level::Owner);
+    };
+    let from_cookie = header(request, "Cookie").and_then(|cookies| {
+        cookies.split(';').find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == access.cookie).then_some(value)
+        })
+    });
+    [from_cookie, header(request, "X-Webrust-Token"), query_token(query)]
+        .into_iter()
+        .flatten()
+        .filter_map(|token| access.level(token.trim()))
+        .min_by_key(|level| *level != Level::Owner)
+}
+
+/// Level of the token in `query`, i.e. when the page was opened from a printed URL.
+pub(crate) fn token_in_query(query: &str) -> Option<Level> {
+    let access = ACCESS.read().unwrap();
+    access.as_ref()?.level(query_token(query)?)
+}
+
+fn query_token(query: &str) -> Option<&str> {
+    query.split('&').find_map(|pair| pair.strip_prefix("token="))
+}
+
+/// `Set-Cookie` header remembering the token of `level` for the tabs of this browser.
+pub(crate) fn session_cookie(level: Level) -> Option<Header> {
+    let access = ACCESS.read().unwrap();
+    let access = access.as_ref()?;
+    let secure = if access.secure { "; Secure" } else { "" };
+    let value = format!("{}={}; Path=/; HttpOnly; SameSite=Strict{}", access.cookie, access.token(level)?, secure);
+    Header::from_bytes(&b"Set-Cookie"[..], value.as_bytes()).ok()
+}
+
+/// Compares in constant time, so the answer time reveals nothing about the token.
+fn same(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiny_http::TestRequest;
+
+    const OWNER: &str = "0123456789abcdef0123456789abcdef";
+    const VIEWER: &str = "fedcba9876543210fedcba9876543210";
+
+    // Toujours la même configuration : les tests partagent ACCESS et tournent en parallèle
+    fn configured() {
+        configure(OWNER.to_string(), Some(VIEWER.to_string()), 8080, true, false);
+    }
+
+    fn request(headers: &[(&str, &str)]) -> Request {
+        headers
+            .iter()
+            .fold(TestRequest::new(), |request, (name, value)| {
+                request.with_header(Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap())
+            })
+            .into()
+    }
+
+    #[test]
+    fn same_compares_whole_tokens() {
+        assert!(same(OWNER, OWNER));
+        assert!(!same(VIEWER, OWNER));
+        assert!(!same(&OWNER[..31], OWNER));
+        assert!(!same("", OWNER));
+    }
+
+    #[test]
+    fn query_token_finds_the_token_parameter() {
+        assert_eq!(query_token("token=abc"), Some("abc"));
+        assert_eq!(query_token("since=3&token=abc"), Some("abc"));
+        assert_eq!(query_token("mytoken=abc"), None);
+        assert_eq!(query_token(""), None);
+    }
+
+    #[test]
+    fn authorized_prefers_the_owner_token_wherever_it_is() {
+        configured();
+        let viewer_cookie = format!("theme=dark; webrust_8080={}", VIEWER);
+        let owner_query = format!("token={}", OWNER);
+        assert_eq!(authorized(&request(&[("Cookie", &viewer_cookie)]), ""), Some(Level::Viewer));
+        assert_eq!(authorized(&request(&[("Cookie", &viewer_cookie)]), &owner_query), Some(Level::Owner));
+        assert_eq!(
+            authorized(&request(&[("Cookie", &viewer_cookie), ("X-Webrust-Token", OWNER)]), ""),
+            Some(Level::Owner)
+        );
+        // Un jeton faux ne masque pas un jeton valide venu d'ailleurs
+        assert_eq!(authorized(&request(&[("X-Webrust-Token", "wrong")]), &owner_query), Some(Level::Owner));
+        assert_eq!(authorized(&request(&[("Cookie", &format!("webrust_9090={}", OWNER))]), ""), None);
+        assert_eq!(authorized(&request(&[]), "token=wrong"), None);
+    }
+
+    #[test]
+    fn allowed_origin_checks_host_and_origin() {
+        configured();
+        assert!(allowed_origin(&request(&[("Host", "127.0.0.1:8080")])));
+        assert!(allowed_origin(&request(&[("Host", "localhost:8080"), ("Origin", "http://localhost:8080")])));
+        assert!(!allowed_origin(&request(&[("Host", "attacker.example:8080")])));
+        assert!(!allowed_origin(&request(&[("Host", "127.0.0.1:9090")])));
+        assert!(!allowed_origin(&request(&[("Host", "127.0.0.1:8080"), ("Origin", "http://attacker.example")])));
+        assert!(!allowed_origin(&request(&[("Host", "127.0.0.1:8080"), ("Origin", "null")])));
+    }
+
+    #[test]
+    fn generated_tokens_are_random_hex() {
+        let token = generate_token();
+        assert_eq!(token.len(), 32);
+        assert!(token.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_ne!(token, generate_token());
+    }
+}
@@ -0,0 +1,311 @@
+//! # Assets Module - Images, Fonts and Custom Styles
+//!
+//! This module serves extra static files to the page under `/assets/`, so
+//! programs can show their own images or restyle the terminal without any
+//! CDN. Files are either embedded in the binary or read from a directory.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use webrust::prelude::*;
+//!
+//! #[gui(assets_dir = "static")]          // serves ./static/* as /assets/*
+//! fn main() {
+//!     register_asset("dot.svg", b"<svg xmlns='http://www.w3.org/2000/svg' width='8' height='8'><circle cx='4' cy='4' r='4'/></svg>");
+//!     add_stylesheet("theme.css");       // <link> to /assets/theme.css
+//!     println_html("<img src=\"/assets/dot.svg\"> <img src=\"/assets/logo.png\">");
+//! }
+//! ```
+//!
+//! Files can be compiled into the binary with
+//! `embed_asset!("logo.png", "../static/logo.png")`, which reads the path
+//! like `include_bytes!`.
+//!
+//! Names with `..`, absolute paths or backslashes, even percent-encoded, are
+//! refused, and so are symbolic links leading out of the served directory.
+//!
+//! Stylesheets and scripts added with [`add_stylesheet`] and [`add_script`]
+//! are linked from the page head, after the built-in stylesheet, so they can
+//! override it. They apply to pages loaded after the call.
+//!
+//! ## Caching
+//!
+//! Every response carries an `ETag` and answers `If-None-Match` with
+//! `304 Not Modified`. Embedded assets may be cached for a day; files read
+//! from a directory and the page itself are revalidated on every load, so
+//! edits show up on refresh.
+
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::sync::RwLock;
+use std::time::UNIX_EPOCH;
+use tiny_http::{Header, Response};
+
+/// How long browsers may reuse a response without asking again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum CachePolicy {
+    /// Always revalidated with the `ETag` (page, built-in script and files on disk).
+    Revalidate,
+    /// Reused for a day (embedded assets, which only change with the binary).
+    Day,
+    /// Never revalidated (content-addressed image blobs).
+    Immutable,
+}
+
+/// A file ready to be sent.
+pub(crate) struct Asset {
+    pub body: Cow<'static, [u8]>,
+    pub content_type: Cow<'static, str>,
+    pub cache: CachePolicy,
+    pub etag: String,
+}
+
+impl Asset {
+    pub(crate) fn new(name: &str, body: Cow<'static, [u8]>, cache: CachePolicy) -> Self {
+        Self::typed(Cow::Borrowed(mime_type(name)), body, cache)
+    }
+
+    pub(crate) fn typed(content_type: Cow<'static, str>, body: Cow<'static, [u8]>, cache: CachePolicy) -> Self {
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        let etag = format!("\"{:x}\"", hasher.finish());
+        Self { content_type, body, cache, etag }
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    embedded: HashMap<String, &'static [u8]>,
+    dirs: Vec<PathBuf>,
+    stylesheets: Vec<String>,
+    scripts: Vec<String>,
+}
+
+lazy_static::lazy_static! {
+    static ref REGISTRY: RwLock<Registry> = RwLock::new(Registry::default());
+}
+
+/// Serves `bytes` at `/assets/<name>`; see also [`embed_asset!`](crate::embed_asset).
+pub fn register_asset(name: &str, bytes: &'static [u8]) {
+    let name = name.trim_start_matches('/').to_string();
+    REGISTRY.write().unwrap().embedded.insert(name, bytes);
+}
+
+/// Serves the files of `dir` (and its subdirectories) under `/assets/`.
+///
+/// Embedded assets take precedence; directories are searched in the order they were added.
+pub fn serve_dir(dir: impl Into<PathBuf>) {
+    REGISTRY.write().unwrap().dirs.push(dir.into());
+}
+
+/// Links `/assets/<name>` as a stylesheet of the page.
+pub fn add_stylesheet(name: &str) {
+    REGISTRY.write().unwrap().stylesheets.push(name.trim_start_matches('/').to_string());
+}
+
+/// Loads `/assets/<name>` as a script of the page, after the built-in script.
+pub fn add_script(name: &str) {
+    REGISTRY.write().unwrap().scripts.push(name.trim_start_matches('/').to_string());
+}
+
+/// Embeds a file in the binary and serves it at `/assets/<name>`.
+///
+/// The path is relative to the file containing the macro call, as with `include_bytes!`.
+#[macro_export]
+macro_rules! embed_asset {
+    ($name:expr, $path:expr) => {
+        $crate::assets::register_asset($name, include_bytes!($path))
+    };
+}
+
+/// Tags added to the page head for the registered stylesheets.
+pub(crate) fn stylesheet_tags() -> String {
+    REGISTRY.read().unwrap().stylesheets
+        .iter()
+        .map(|name| format!("<link rel=\"stylesheet\" href=\"/assets/{}\">", attribute(name)))
+        .collect::<Vec<_>>()
+        .join("\n  ")
+}
+
+/// Tags added at the end of the page for the registered scripts.
+pub(crate) fn script_tags() -> String {
+    REGISTRY.read().unwrap().scripts
+        .iter()
+        .map(|name| format!("<script src=\"/assets/{}\"></script>", attribute(name)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn attribute(name: &str) -> String {
+    name.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;")
+}
+
+/// Looks up `/assets/<name>`: embedded assets first, then the registered directories.
+pub(crate) fn find(name: &str) -> Option<Asset> {
+    let name = percent_decode(name)?;
+    let relative = safe_relative_path(&name)?;
+    let registry = REGISTRY.read().unwrap();
+    if let Some(bytes) = registry.embedded.get(&name) {
+        return Some(Asset::new(&name, Cow::Borrowed(*bytes), CachePolicy::Day));
+    }
+    registry.dirs.iter().find_map(|dir| {
+        // Un lien symbolique du dossier ne doit pas mener ailleurs
+        let path = fs::canonicalize(dir.join(&relative)).ok()?;
+        if !path.starts_with(fs::canonicalize(dir).ok()?) {
+            return None;
+        }
+        let metadata = fs::metadata(&path).ok().filter(|metadata| metadata.is_file())?;
+        let body = fs::read(&path).ok()?;
+        let mut asset = Asset::new(&name, Cow::Owned(body), CachePolicy::Revalidate);
+        // Date et taille suffisent à détecter une modification du fichier
+        if let Ok(modified) = metadata.modified() {
+            let nanos = modified.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+            asset.etag = format!("\"{:x}-{:x}\"", nanos, metadata.len());
+        }
+        Some(asset)
+    })
+}
+
+/// Rejects absolute paths and `..`, so only files inside the directories are
+/// reachable; [`find`] also checks where symbolic links lead.
+fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    if name.is_empty() || name.contains('\\') || name.contains('\0') {
+        return None;
+    }
+    let path = Path::new(name);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = text.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Sends `asset`, or `304 Not Modified` when the browser already has this version.
+pub(crate) fn respond(request: tiny_http::Request, asset: Asset) {
+    respond_with(request, asset, None);
+}
+
+/// Same as [`respond`], with more headers (e.g. `Set-Cookie`).
+pub(crate) fn respond_with(request: tiny_http::Request, asset: Asset, extra: impl IntoIterator<Item = Header>) {
+    let cache_control = match asset.cache {
+        CachePolicy::Revalidate => "no-cache",
+        CachePolicy::Day => "public, max-age=86400",
+        CachePolicy::Immutable => "public, max-age=31536000, immutable",
+    };
+    let headers = [
+        Header::from_bytes(&b"Content-Type"[..], asset.content_type.as_bytes()).unwrap(),
+        Header::from_bytes(&b"Cache-Control"[..], cache_control.as_bytes()).unwrap(),
+        Header::from_bytes(&b"ETag"[..], asset.etag.as_bytes()).unwrap(),
+    ];
+    let not_modified = request.headers().iter()
+        .find(|header| header.field.equiv("If-None-Match"))
+        .is_some_and(|header| header.value.as_str().split(',').any(|tag| tag.trim() == asset.etag));
+    let response = if not_modified {
+        Response::from_data(Vec::new()).with_status_code(304)
+    } else {
+        Response::from_data(asset.body.into_owned())
+    };
+    let response = headers.into_iter().chain(extra).fold(response, |response, header| response.with_header(header));
+    let _ = request.respond(response);
+}
+
+/// Content type for a file name, from its extension.
+pub(crate) fn mime_type(name: &str) -> &'static str {
+    let extension = Path::new(name).extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "txt" => "text/plain; charset=utf-8",
+        "md" => "text/markdown; charset=utf-8",
+        "csv" => "text/csv; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "wasm" => "application/wasm",
+        "pdf" => "application/pdf",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_relative_paths_are_safe() {
+        assert_eq!(safe_relative_path("img/logo.png"), Some(PathBuf::from("img/logo.png")));
+        for unsafe_name in ["", "..", "../secret", "img/../../secret", "./logo.png", "/etc/passwd", "img\\..\\secret", "a\0b"] {
+            assert_eq!(safe_relative_path(unsafe_name), None, "{:?}", unsafe_name);
+        }
+    }
+
+    #[test]
+    fn encoded_traversals_are_decoded_before_the_check() {
+        assert_eq!(percent_decode("my%20logo.png").as_deref(), Some("my logo.png"));
+        for encoded in ["%2e%2e/secret", "%2E%2E%2Fsecret", "img%2f..%2f..%2fsecret", "%2fetc%2fpasswd", "img%5c..%5csecret"] {
+            let decoded = percent_decode(encoded).unwrap();
+            assert_eq!(safe_relative_path(&decoded), None, "{:?} -> {:?}", encoded, decoded);
+        }
+        // Séquences tronquées ou non UTF-8 : refusées plutôt que devinées
+        for broken in ["%2", "%zz", "%ff"] {
+            assert_eq!(percent_decode(broken), None, "{:?}", broken);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symbolic_links_cannot_leave_the_directory() {
+        let root = std::env::temp_dir().join(format!("webrust-assets-{}", std::process::id()));
+        let dir = root.join("public");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("inside.txt"), "inside").unwrap();
+        fs::write(root.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(dir.join("inside.txt"), dir.join("alias.txt")).unwrap();
+        std::os::unix::fs::symlink(root.join("secret.txt"), dir.join("escape.txt")).unwrap();
+        serve_dir(&dir);
+
+        let served = |name: &str| find(name).map(|asset| String::from_utf8(asset.body.into_owned()).unwrap());
+        let results = (served("inside.txt"), served("alias.txt"), served("escape.txt"));
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(results.0.as_deref(), Some("inside"));
+        assert_eq!(results.1.as_deref(), Some("inside"));
+        assert_eq!(results.2, None);
+    }
+}
@@ -0,0 +1,140 @@
+//! # Async Module - webrust on a Tokio Runtime
+//!
+//! Available with the `async` feature. It lets programs that already run on
+//! tokio use webrust without blocking their executor threads.
+//!
+//! ## Usage
+//!
+//! Start the interface with [`GuiHandle::start`] instead of `#[gui]`, await
+//! [`input_async`] for answers, and hand the program's result to
+//! [`GuiHandle::finish`] at the end:
+//!
+//! ```rust,no_run
+//! use webrust::async_gui::{input_async, GuiHandle};
+//! use webrust::gui::GuiConfig;
+//! use webrust::print::println_str;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let gui = GuiHandle::start(GuiConfig::new().apply_env()).unwrap();
+//!     let name: String = input_async("Your name:").await;
+//!     println_str(format!("Hello {}!", name));
+//!     gui.finish(()).await;
+//! }
+//! ```
+//!
+//! ## How It Works
+//!
+//! tiny_http is synchronous, so the request loop runs on tokio's blocking
+//! thread pool. An input request registers a callback with the backend; the
+//! answer is sent through a oneshot channel, so awaiting it parks only the
+//! current task. Output functions (`println`, `table`, ...) never block and
+//! can be called from any task.
+//!
+//! Unlike `#[gui]`, panics are not caught: a panicking task is reported by
+//! tokio as usual.
+//! Dropping the handle without calling `finish` stops the server at once.
+
+use crate::backend;
+use crate::gui::{
+    launch, mark_finished, report_result, serve_requests, should_shut_down, stop_server, GuiConfig, ProgramResult,
+    ShutdownPolicy,
+};
+use crate::input::{parse_validator, InputKind};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tiny_http::Server;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// Asks for a value of type `T` without blocking the runtime.
+///
+/// Invalid answers are rejected by the browser (or re-asked in the terminal),
+/// exactly like [`input`](crate::input::input).
+pub async fn input_async<T>(prompt: &str) -> T
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    loop {
+        let (tx, rx) = oneshot::channel();
+        backend::current().request_input(
+            prompt.to_string(),
+            InputKind::for_type::<T>(),
+            parse_validator::<T>(),
+            Box::new(move |answer| {
+                let _ = tx.send(answer);
+            }),
+        );
+        if let Ok(value) = rx.await.unwrap_or_default().trim().parse() {
+            return value;
+        }
+    }
+}
+
+/// A running web interface started on the current tokio runtime.
+pub struct GuiHandle {
+    server: Option<Arc<Server>>,
+    serve_task: Option<JoinHandle<()>>,
+    shutdown: ShutdownPolicy,
+    url: Option<String>,
+}
+
+impl GuiHandle {
+    /// Starts the server and opens the browser; must be called from within a runtime.
+    ///
+    /// With the terminal backend or a replayed transcript no server is started
+    /// and output goes to stdout.
+    pub fn start(config: GuiConfig) -> Result<GuiHandle, String> {
+        if !config.install_backend()? {
+            return Ok(GuiHandle { server: None, serve_task: None, shutdown: config.shutdown, url: None });
+        }
+
+        let (server, url) = launch(&config)?;
+        let server = Arc::new(server);
+        let serve_task = {
+            let server = Arc::clone(&server);
+            tokio::task::spawn_blocking(move || serve_requests(&server))
+        };
+        Ok(GuiHandle {
+            server: Some(server),
+            serve_task: Some(serve_task),
+            shutdown: config.shutdown,
+            url: Some(url),
+        })
+    }
+
+    /// Address of the web interface, or `None` with the terminal backend.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Reports `result`, waits for the shutdown policy, stops the server and returns `result`.
+    pub async fn finish<R: ProgramResult>(mut self, result: R) -> R {
+        let Some(server) = self.server.take() else {
+            return result;
+        };
+        let finished_at = mark_finished(report_result(&result));
+        while !should_shut_down(self.shutdown, finished_at) {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        stop_server(&server);
+        if let Some(task) = self.serve_task.take() {
+            let _ = task.await;
+        }
+        println!("🚀 Content served successfully! Closing server...");
+        result
+    }
+}
+
+/// A handle dropped without [`GuiHandle::finish`] (early return, `?`, panic)
+/// still stops the server, so the runtime does not wait for it on shutdown.
+impl Drop for GuiHandle {
+    fn drop(&mut self) {
+        if let Some(server) = self.server.take() {
+            stop_server(&server);
+        }
+    }
+}
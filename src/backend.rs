@@ -0,0 +1,576 @@
+//! # Backend Module - Pluggable Output and Input Targets
+//!
+//! Every `print`, `println`, `input` and `latex` call ends up in
+//! [`gui::add_output_new_line`](crate::gui::add_output_new_line),
+//! [`gui::add_output_same_line`](crate::gui::add_output_same_line) or
+//! [`gui::create_input_request_typed`](crate::gui::create_input_request_typed).
+//! These functions forward to the active [`Backend`], which decides where the
+//! output goes and where the input comes from.
+//!
+//! ## Available Backends
+//!
+//! - [`WebBackend`]: the default, renders into the browser terminal
+//! - [`TerminalBackend`]: for SSH sessions and CI, writes to stdout and reads from stdin
+//! - [`RecordingBackend`](crate::transcript::RecordingBackend) and
+//!   [`ReplayBackend`](crate::transcript::ReplayBackend): write a transcript of
+//!   another backend, or answer inputs from one
+//!
+//! ## Selecting a Backend
+//!
+//! ```rust,no_run
+//! use webrust::prelude::*;
+//!
+//! #[gui(backend = "terminal")]
+//! fn main() {
+//!     let name: String = input("Name:");
+//!     println("Hello @(green, bold){name}@(reset)!");
+//! }
+//! ```
+//!
+//! The `WEBRUST_BACKEND` environment variable (`web` or `terminal`) overrides
+//! the attribute, so the same binary can run headless:
+//!
+//! ```bash
+//! WEBRUST_BACKEND=terminal cargo run --example simpleio
+//! ```
+//!
+//! ## Terminal Rendering
+//!
+//! - `@(red, bold)` style markers become ANSI escape codes (disabled when `NO_COLOR` is set)
+//! - `input` reads a line from stdin and re-prompts with the same validation
+//!   rules as the web interface
+//! - `latex` prints a Unicode approximation of the formula, or the TeX source
+//!   verbatim with [`TerminalBackend::verbatim_latex`]
+//! - tables are drawn as Markdown-style pipe tables; charts are only named
+
+use crate::gui::WebBackend;
+use crate::input::{InputKind, Reply, Validator};
+use crate::output::OutputItem;
+use crate::print::{process_styles, process_styles_ansi, text_to_html};
+use crate::table::{Align, TableData};
+use regex::Regex;
+use std::cell::RefCell;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+/// Destination for program output and source of user input.
+pub trait Backend: Send + Sync + 'static {
+    /// Appends to the last output record when possible (HTML after HTML).
+    fn add_output_same_line(&self, item: OutputItem);
+
+    /// Adds a new output record.
+    fn add_output_new_line(&self, item: OutputItem);
+
+    /// Asks the user for a value and blocks until one accepted by `kind` and `validator` is provided.
+    fn create_input_request(&self, prompt: &str, kind: &InputKind, validator: Validator) -> String;
+
+    /// Starts an input request and returns at once; `reply` receives the answer.
+    /// The default implementation waits for [`create_input_request`](Backend::create_input_request)
+    /// on a separate thread.
+    fn request_input(self: Arc<Self>, prompt: String, kind: InputKind, validator: Validator, reply: Reply) {
+        thread::spawn(move || reply(self.create_input_request(&prompt, &kind, validator)));
+    }
+
+    /// Prints text containing `@(style)` markers; the text itself is escaped.
+    fn print(&self, text: &str, newline: bool) {
+        push_html(self, text_to_html(text), newline);
+    }
+
+    /// Prints trusted markup; only the styling markers are processed.
+    fn print_html(&self, html: &str, newline: bool) {
+        push_html(self, process_styles(html), newline);
+    }
+
+    /// Whether image data must be kept for the page to fetch from `/api/blob/`.
+    fn serves_images(&self) -> bool {
+        false
+    }
+
+    /// Output records kept so far, in display order, for [`crate::export`];
+    /// `None` when the backend does not keep them (e.g. the terminal).
+    fn output_items(&self) -> Option<Vec<OutputItem>> {
+        None
+    }
+}
+
+fn push_html<B: Backend + ?Sized>(backend: &B, html: String, newline: bool) {
+    let item = html_item(html, newline);
+    if newline {
+        backend.add_output_new_line(item);
+    } else {
+        backend.add_output_same_line(item);
+    }
+}
+
+/// The record produced by `print` (`newline == false`) or `println`.
+pub(crate) fn html_item(html: String, newline: bool) -> OutputItem {
+    if newline {
+        OutputItem::html(format!("{}<br>", html))
+    } else {
+        OutputItem::html(html)
+    }
+}
+
+/// Built-in backends selectable from `#[gui(backend = "...")]` or `WEBRUST_BACKEND`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackendKind {
+    Web,
+    Terminal,
+}
+
+impl FromStr for BackendKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "web" | "gui" | "browser" => Ok(BackendKind::Web),
+            "terminal" | "term" | "tty" => Ok(BackendKind::Terminal),
+            other => Err(format!("unknown backend '{}', expected 'web' or 'terminal'", other)),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref BACKEND: RwLock<Arc<dyn Backend>> = RwLock::new(Arc::new(WebBackend));
+}
+
+/// Replaces the active backend for the whole program.
+pub fn set_backend<B: Backend + 'static>(backend: B) {
+    *BACKEND.write().unwrap() = Arc::new(backend);
+}
+
+thread_local! {
+    static THREAD_BACKEND: RefCell<Option<Arc<dyn Backend>>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn current() -> Arc<dyn Backend> {
+    THREAD_BACKEND.with(|backend| backend.borrow().clone())
+        .unwrap_or_else(|| BACKEND.read().unwrap().clone())
+}
+
+/// Whether the calling thread uses its own backend (see [`ThreadBackendGuard`]).
+pub(crate) fn has_thread_backend() -> bool {
+    THREAD_BACKEND.with(|backend| backend.borrow().is_some())
+}
+
+/// Restores the previous backend of the calling thread when dropped.
+pub(crate) struct ThreadBackendGuard {
+    previous: Option<Arc<dyn Backend>>,
+}
+
+/// Uses `backend` on the calling thread only, until the returned guard is dropped.
+pub(crate) fn set_thread_backend(backend: Arc<dyn Backend>) -> ThreadBackendGuard {
+    let previous = THREAD_BACKEND.with(|current| current.borrow_mut().replace(backend));
+    ThreadBackendGuard { previous }
+}
+
+impl Drop for ThreadBackendGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        THREAD_BACKEND.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Headless backend writing ANSI-styled output to stdout and reading input from stdin.
+pub struct TerminalBackend {
+    verbatim_latex: bool,
+}
+
+impl Default for TerminalBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TerminalBackend {
+    pub fn new() -> Self {
+        Self { verbatim_latex: false }
+    }
+
+    /// Prints LaTeX source as written instead of a Unicode approximation.
+    pub fn verbatim_latex(mut self, enabled: bool) -> Self {
+        self.verbatim_latex = enabled;
+        self
+    }
+
+    fn render_item(&self, item: &OutputItem) -> String {
+        match item {
+            OutputItem::Text { text } => text.clone(),
+            OutputItem::Html { html } => strip_html(html),
+            OutputItem::Latex { formula, display: true } => format!("    {}\n", self.render_latex(formula)),
+            OutputItem::Latex { formula, display: false } => format!("{}\n", self.render_latex(formula)),
+            OutputItem::InputRequest { prompt, .. } => format!("{} ", process_styles_ansi(prompt)),
+            OutputItem::InputResponse { value, .. } => format!("{}\n", value),
+            OutputItem::Table(data) => render_table(data),
+            OutputItem::Plot { title, .. } => format!("[chart: {}]\n", title.as_deref().unwrap_or("untitled")),
+            OutputItem::Image { mime, caption, .. } => format!("[image: {}]\n", caption.as_deref().unwrap_or(mime)),
+            OutputItem::Error { message } => format!("{}\n", process_styles_ansi(&format!("@(red)❌ Error: {}", message))),
+            // Le hook de panique a déjà tout écrit sur stderr
+            OutputItem::Panic(_) => String::new(),
+        }
+    }
+
+    fn write_text(&self, rendered: &str, newline: bool) {
+        if newline {
+            println!("{}", rendered);
+        } else {
+            print!("{}", rendered);
+            let _ = io::stdout().flush();
+        }
+    }
+
+    fn render_latex(&self, formula: &str) -> String {
+        if self.verbatim_latex {
+            formula.to_string()
+        } else {
+            latex_to_unicode(formula)
+        }
+    }
+}
+
+impl Backend for TerminalBackend {
+    fn add_output_same_line(&self, item: OutputItem) {
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "{}", self.render_item(&item));
+        let _ = stdout.flush();
+    }
+
+    fn add_output_new_line(&self, item: OutputItem) {
+        let rendered = self.render_item(&item);
+        if rendered.is_empty() {
+            return;
+        }
+        if rendered.ends_with('\n') {
+            print!("{}", rendered);
+        } else {
+            println!("{}", rendered);
+        }
+    }
+
+    fn create_input_request(&self, prompt: &str, kind: &InputKind, validator: Validator) -> String {
+        if let InputKind::Select { options } = kind {
+            for (index, option) in options.iter().enumerate() {
+                println!("  {}) {}", index + 1, option);
+            }
+        }
+        loop {
+            print!("{}{} ", process_styles_ansi(prompt), terminal_hint(kind));
+            let _ = io::stdout().flush();
+            let line = match kind {
+                InputKind::Textarea => read_block(prompt),
+                InputKind::Password => read_secret(prompt),
+                _ => read_line(prompt),
+            };
+            let checked = terminal_value(kind, &line)
+                .and_then(|value| kind.validate(&value).and_then(|()| validator(&value)).map(|()| value));
+            match checked {
+                Ok(value) => return value,
+                Err(error) => println!("{}", process_styles_ansi(&format!("@(red)❌ {}", error))),
+            }
+        }
+    }
+
+    fn print(&self, text: &str, newline: bool) {
+        self.write_text(&process_styles_ansi(text), newline);
+    }
+
+    fn print_html(&self, html: &str, newline: bool) {
+        self.write_text(&process_styles_ansi(&strip_html(html)), newline);
+    }
+}
+
+fn terminal_hint(kind: &InputKind) -> String {
+    match kind {
+        InputKind::Select { options } => format!(" [1-{}]", options.len()),
+        InputKind::Confirm => " [y/n]".to_string(),
+        InputKind::Slider { min, max, .. } => format!(" [{}-{}]", min, max),
+        InputKind::Date => " (YYYY-MM-DD)".to_string(),
+        InputKind::Textarea => " (end with an empty line)\n".to_string(),
+        InputKind::Text { default: Some(default), .. } => format!(" [{}]", default),
+        InputKind::Text { .. } | InputKind::Password => String::new(),
+    }
+}
+
+/// Stops the program when stdin has nothing more to give (e.g. `</dev/null` in CI).
+fn stdin_closed(prompt: &str) -> ! {
+    panic!(
+        "stdin closed while waiting for input: {}\n\
+         The terminal backend reads answers from stdin; to run without one, replay a \
+         transcript with WEBRUST_REPLAY or feed the answers with webrust::testing::Harness",
+        prompt
+    )
+}
+
+/// Reads one line, without its line ending.
+fn read_line(prompt: &str) -> String {
+    let mut line = String::new();
+    match io::stdin().lock().read_line(&mut line) {
+        Ok(0) | Err(_) => stdin_closed(prompt),
+        Ok(_) => line.trim_end_matches(['\r', '\n']).to_string(),
+    }
+}
+
+/// Reads lines up to the first empty one.
+fn read_block(prompt: &str) -> String {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line) {
+            Ok(0) | Err(_) if lines.is_empty() => stdin_closed(prompt),
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line.to_string());
+    }
+    lines.join("\n")
+}
+
+/// Reads a line with the typed characters hidden (ANSI conceal) on a terminal.
+fn read_secret(prompt: &str) -> String {
+    let conceal = io::stdout().is_terminal();
+    if conceal {
+        print!("\x1b[8m");
+        let _ = io::stdout().flush();
+    }
+    let line = read_line(prompt);
+    if conceal {
+        print!("\x1b[28m");
+        let _ = io::stdout().flush();
+    }
+    line
+}
+
+/// Converts what was typed into the value the web widget would have sent.
+pub(crate) fn terminal_value(kind: &InputKind, line: &str) -> Result<String, String> {
+    let trimmed = line.trim();
+    match kind {
+        InputKind::Password | InputKind::Textarea | InputKind::Text { default: Some(_), .. } => Ok(line.to_string()),
+        _ if trimmed.is_empty() => Err("Please enter a value".to_string()),
+        InputKind::Select { options } => {
+            let by_number = trimmed.parse::<usize>().ok().filter(|n| (1..=options.len()).contains(n)).map(|n| n - 1);
+            let by_name = || options.iter().position(|option| option.eq_ignore_ascii_case(trimmed));
+            by_number
+                .or_else(by_name)
+                .map(|index| index.to_string())
+                .ok_or_else(|| format!("Please choose a number between 1 and {}", options.len()))
+        }
+        InputKind::Confirm => match trimmed.to_lowercase().as_str() {
+            "y" | "yes" => Ok("true".to_string()),
+            "n" | "no" => Ok("false".to_string()),
+            _ => Err("Please answer y or n".to_string()),
+        },
+        _ => Ok(line.to_string()),
+    }
+}
+
+pub(crate) fn render_table(data: &TableData) -> String {
+    let headers: Vec<String> = data.headers.iter().map(|header| strip_html(header)).collect();
+    let shown = data.max_rows.unwrap_or(data.rows.len()).min(data.rows.len());
+    let rows: Vec<Vec<String>> = data.rows[..shown]
+        .iter()
+        .map(|row| row.iter().map(|cell| strip_html(&cell.html)).collect())
+        .collect();
+
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    for row in &rows {
+        for (index, cell) in row.iter().enumerate() {
+            widths[index] = widths[index].max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                let width = widths[index];
+                match data.align.get(index) {
+                    Some(Align::Right) => format!("{:>width$}", cell),
+                    Some(Align::Center) => format!("{:^width$}", cell),
+                    _ => format!("{:<width$}", cell),
+                }
+            })
+            .collect();
+        format!("| {} |\n", padded.join(" | "))
+    };
+    let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+    let mut output = format_row(&headers);
+    output.push_str(&format!("|-{}-|\n", separator.join("-|-")));
+    for row in &rows {
+        output.push_str(&format_row(row));
+    }
+    if shown < data.rows.len() {
+        let hidden = data.rows.len() - shown;
+        output.push_str(&format!("… {} more row{}\n", hidden, if hidden == 1 { "" } else { "s" }));
+    }
+    output
+}
+
+pub(crate) fn strip_html(text: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref BR_REGEX: Regex = Regex::new(r"(?i)<br\s*/?>").unwrap();
+        static ref TAG_REGEX: Regex = Regex::new(r"<[^>]*>").unwrap();
+    }
+    let text = BR_REGEX.replace_all(text, "\n");
+    TAG_REGEX.replace_all(&text, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// Best-effort Unicode rendering of common TeX constructs for plain terminals.
+fn latex_to_unicode(formula: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref FRAC_REGEX: Regex = Regex::new(r"\\[dt]?frac\{([^{}]*)\}\{([^{}]*)\}").unwrap();
+        static ref SQRT_REGEX: Regex = Regex::new(r"\\sqrt\{([^{}]*)\}").unwrap();
+        static ref TEXT_REGEX: Regex = Regex::new(r"\\(?:text|mathrm|mathbf|mathit|operatorname)\{([^{}]*)\}").unwrap();
+        static ref NEWLINE_REGEX: Regex = Regex::new(r"\\\\(?:\[[^\]]*\])?").unwrap();
+        static ref ENV_REGEX: Regex = Regex::new(r"\\(?:begin|end)\{[^{}]*\}(?:\{[^{}]*\})?").unwrap();
+        static ref SUP_REGEX: Regex = Regex::new(r"\^\{([^{}]*)\}|\^(.)").unwrap();
+        static ref SUB_REGEX: Regex = Regex::new(r"_\{([^{}]*)\}|_(.)").unwrap();
+        static ref COMMAND_REGEX: Regex = Regex::new(r"\\([a-zA-Z]+)").unwrap();
+    }
+    const SYMBOLS: &[(&str, &str)] = &[
+        ("alpha", "α"), ("beta", "β"), ("gamma", "γ"), ("delta", "δ"), ("epsilon", "ε"),
+        ("varepsilon", "ε"), ("zeta", "ζ"), ("eta", "η"), ("theta", "θ"), ("iota", "ι"),
+        ("kappa", "κ"), ("lambda", "λ"), ("mu", "μ"), ("nu", "ν"), ("xi", "ξ"), ("pi", "π"),
+        ("rho", "ρ"), ("sigma", "σ"), ("tau", "τ"), ("upsilon", "υ"), ("phi", "φ"),
+        ("varphi", "φ"), ("chi", "χ"), ("psi", "ψ"), ("omega", "ω"), ("Gamma", "Γ"),
+        ("Delta", "Δ"), ("Theta", "Θ"), ("Lambda", "Λ"), ("Xi", "Ξ"), ("Pi", "Π"),
+        ("Sigma", "Σ"), ("Phi", "Φ"), ("Psi", "Ψ"), ("Omega", "Ω"),
+        ("times", "×"), ("div", "÷"), ("pm", "±"), ("mp", "∓"), ("cdot", "·"), ("ast", "∗"),
+        ("leq", "≤"), ("le", "≤"), ("geq", "≥"), ("ge", "≥"), ("neq", "≠"), ("ne", "≠"),
+        ("approx", "≈"), ("equiv", "≡"), ("sim", "∼"), ("propto", "∝"), ("infty", "∞"),
+        ("sum", "∑"), ("prod", "∏"), ("int", "∫"), ("iint", "∬"), ("iiint", "∭"), ("oint", "∮"),
+        ("partial", "∂"), ("nabla", "∇"), ("sqrt", "√"), ("in", "∈"), ("notin", "∉"),
+        ("subset", "⊂"), ("subseteq", "⊆"), ("supset", "⊃"), ("cup", "∪"), ("cap", "∩"),
+        ("emptyset", "∅"), ("forall", "∀"), ("exists", "∃"), ("neg", "¬"), ("land", "∧"),
+        ("lor", "∨"), ("to", "→"), ("rightarrow", "→"), ("leftarrow", "←"),
+        ("Rightarrow", "⇒"), ("Leftarrow", "⇐"), ("leftrightarrow", "↔"),
+        ("Leftrightarrow", "⇔"), ("mapsto", "↦"), ("ldots", "…"), ("cdots", "⋯"),
+        ("vec", ""), ("hat", ""), ("bar", ""), ("left", ""), ("right", ""), ("hline", ""),
+        ("quad", "  "), ("qquad", "    "), ("lim", "lim"), ("log", "log"), ("ln", "ln"),
+        ("sin", "sin"), ("cos", "cos"), ("tan", "tan"), ("exp", "exp"), ("max", "max"),
+        ("min", "min"),
+    ];
+
+    let mut result = NEWLINE_REGEX.replace_all(formula, "\n").replace("\\,", " ").replace("\\;", " ")
+        .replace("\\!", "").replace("\\{", "{").replace("\\}", "}").replace('&', " ");
+    result = ENV_REGEX.replace_all(&result, "").to_string();
+    result = TEXT_REGEX.replace_all(&result, "$1").to_string();
+    // Les fractions et racines peuvent être imbriquées : on répète jusqu'à stabilité
+    loop {
+        let next = SQRT_REGEX.replace_all(&result, "√($1)");
+        let next = FRAC_REGEX.replace_all(&next, "($1)/($2)").to_string();
+        if next == result {
+            break;
+        }
+        result = next;
+    }
+    result = COMMAND_REGEX.replace_all(&result, |caps: &regex::Captures| {
+        SYMBOLS.iter()
+            .find(|(name, _)| *name == &caps[1])
+            .map(|(_, symbol)| symbol.to_string())
+            .unwrap_or_else(|| caps[1].to_string())
+    }).to_string();
+    result = SUP_REGEX.replace_all(&result, |caps: &regex::Captures| {
+        let content = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+        map_script(content, "0123456789+-=()ni", "⁰¹²³⁴⁵⁶⁷⁸⁹⁺⁻⁼⁽⁾ⁿⁱ", '^')
+    }).to_string();
+    result = SUB_REGEX.replace_all(&result, |caps: &regex::Captures| {
+        let content = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+        map_script(content, "0123456789+-=()aeioxn", "₀₁₂₃₄₅₆₇₈₉₊₋₌₍₎ₐₑᵢₒₓₙ", '_')
+    }).to_string();
+    result.replace(['{', '}'], "")
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n    ")
+}
+
+fn map_script(content: &str, from: &str, to: &str, marker: char) -> String {
+    let mapped: Option<String> = content.chars()
+        .map(|c| from.chars().position(|f| f == c).and_then(|i| to.chars().nth(i)))
+        .collect();
+    mapped.unwrap_or_else(|| {
+        if content.chars().count() == 1 {
+            format!("{}{}", marker, content)
+        } else {
+            format!("{}({})", marker, content)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::print::styles_to_ansi;
+    use crate::table::TableCell;
+
+    #[test]
+    fn terminal_answers_are_converted_to_widget_values() {
+        let select = InputKind::Select { options: vec!["Red".to_string(), "Green".to_string()] };
+        assert_eq!(terminal_value(&select, "2"), Ok("1".to_string()));
+        assert_eq!(terminal_value(&select, " green "), Ok("1".to_string()));
+        assert!(terminal_value(&select, "3").is_err());
+        assert!(terminal_value(&select, "0").is_err());
+
+        assert_eq!(terminal_value(&InputKind::Confirm, "Y"), Ok("true".to_string()));
+        assert_eq!(terminal_value(&InputKind::Confirm, "no"), Ok("false".to_string()));
+        // Seul ce qu'annonce l'invite [y/n] est accepté
+        for refused in ["oui", "non", "true", "maybe"] {
+            assert!(terminal_value(&InputKind::Confirm, refused).is_err(), "{}", refused);
+        }
+
+        assert_eq!(terminal_value(&InputKind::text("i32"), "  "), Err("Please enter a value".to_string()));
+        let with_default = InputKind::Text { value_type: "i32".to_string(), default: Some("18".to_string()), value: None, placeholder: None };
+        assert_eq!(terminal_value(&with_default, ""), Ok(String::new()));
+        assert_eq!(terminal_value(&InputKind::Password, " secret "), Ok(" secret ".to_string()));
+    }
+
+    #[test]
+    fn formulas_are_approximated_in_unicode() {
+        assert_eq!(latex_to_unicode("x^2 + y_1"), "x² + y₁");
+        assert_eq!(latex_to_unicode("\\alpha \\leq \\beta"), "α ≤ β");
+        assert_eq!(latex_to_unicode("\\frac{1}{\\sqrt{2}}"), "(1)/(√(2))");
+        assert_eq!(latex_to_unicode("\\sum_{i=1}^{n} i"), "∑ᵢ₌₁ⁿ i");
+        // Sans indice Unicode pour k, le marqueur reste visible
+        assert_eq!(latex_to_unicode("\\sum_{k=1}^{n} k"), "∑_(k=1)ⁿ k");
+        assert_eq!(latex_to_unicode("x^{y}"), "x^y");
+        assert_eq!(latex_to_unicode("\\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\end{pmatrix}"), "1 0\n    0 1");
+    }
+
+    #[test]
+    fn style_markers_become_ansi_codes() {
+        assert_eq!(styles_to_ansi("@(red, bold)Error@(reset) done", true), "\x1b[31;1mError\x1b[0m done");
+        assert_eq!(styles_to_ansi("@(red, bold)Error@(reset) done", false), "Error done");
+        assert_eq!(styles_to_ansi("@(sparkly)plain", true), "plain");
+    }
+
+    #[test]
+    fn tables_are_aligned_and_truncated() {
+        let cell = |html: &str| TableCell { html: html.to_string(), number: None };
+        let data = TableData {
+            headers: vec!["Name".to_string(), "Score".to_string()],
+            align: vec![Align::Left, Align::Right],
+            rows: vec![vec![cell("Ada"), cell("93")], vec![cell("<b>Grace</b>"), cell("100")], vec![cell("Alan"), cell("7")]],
+            max_rows: Some(2),
+            sortable: false,
+        };
+        assert_eq!(
+            render_table(&data),
+            "| Name  | Score |\n|-------|-------|\n| Ada   |    93 |\n| Grace |   100 |\n… 1 more row\n"
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! # Export Module - Saving a Session
+//!
+//! This module saves the output of the current web session so it can be
+//! attached to a report. The same exports are offered by the "Download" menu
+//! of the page, which also has a print view for saving as PDF.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use webrust::prelude::*;
+//!
+//! #[gui]
+//! fn main() {
+//!     println("@(green, bold)Results");
+//!     latex("\\sum_{i=1}^{n} i = \\frac{n(n+1)}{2}");
+//!
+//!     export_html("results.html").unwrap();
+//!     export_markdown("results.md").unwrap();
+//! }
+//! ```
+//!
+//! ## Formats
+//!
+//! - **HTML**: a single file with the page stylesheet inlined; formulas are
+//!   typeset by MathJax when the file is opened (or by the inlined KaTeX with
+//!   the `offline-math` feature), charts are inline SVG and images data URIs.
+//!   Without `offline-math`, MathJax is loaded from its CDN (or from the
+//!   configured `math_url`), so formulas stay raw TeX on a machine offline
+//! - **Markdown**: `@(bold)` becomes `**strong**`, `@(strike)` becomes
+//!   `~~strike~~` and other styles (colors, italic) become `*emphasis*`;
+//!   formulas are written as `$...$` or `$$...$$`, tables as pipe tables and
+//!   charts and images as data URIs
+//!
+//! Input fields show the submitted answers; passwords stay masked.
+//!
+//! The output is taken from the active backend: the web session, or the run
+//! of a [`Harness`](crate::testing::Harness). The terminal backend and replays
+//! keep no output, so exporting from them fails with
+//! [`io::ErrorKind::Unsupported`].
+
+use crate::backend::{self, strip_html};
+use crate::latex::standalone_math_head;
+use crate::media::data_uri;
+use crate::output::OutputItem;
+use crate::print::{escape_html, process_styles, text_to_html};
+use crate::table::{Align, TableData};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Writes the session output as a standalone HTML file.
+pub fn export_html(path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, render_html(&output_items()?))
+}
+
+/// Writes the session output as Markdown.
+pub fn export_markdown(path: impl AsRef<Path>) -> io::Result<()> {
+    fs::write(path, render_markdown(&output_items()?))
+}
+
+fn output_items() -> io::Result<Vec<OutputItem>> {
+    backend::current().output_items().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::Unsupported, "this backend keeps no output to export (use the web backend or the test harness)")
+    })
+}
+
+/// Answers by input id, to show each request with its response.
+fn responses(items: &[OutputItem]) -> HashMap<&str, &str> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            OutputItem::InputResponse { id, value } => Some((id.as_str(), value.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Renders output records as a standalone HTML page.
+pub(crate) fn render_html(items: &[OutputItem]) -> String {
+    let answers = responses(items);
+    let mut body = String::new();
+    for item in items {
+        let line = match item {
+            OutputItem::Html { html } => format!("<div class=\"terminal-line\">{}</div>", html),
+            OutputItem::Text { text } => format!("<div class=\"terminal-line\">{}</div>", escape_html(text)),
+            OutputItem::Latex { formula, display: true } => format!(
+                "<div class=\"terminal-line\"><div class=\"latex-display\" data-formula=\"{0}\">$${0}$$</div></div>",
+                escape_html(formula)
+            ),
+            OutputItem::Latex { formula, display: false } => format!(
+                "<div class=\"terminal-line\"><span class=\"latex-inline\" data-formula=\"{0}\">${0}$</span></div>",
+                escape_html(formula)
+            ),
+            OutputItem::InputRequest { id, prompt, .. } => {
+                let answer = answers.get(id.as_str()).copied().unwrap_or("");
+                format!(
+                    "<div class=\"terminal-line\"><span class=\"input-prompt\">{}</span> <span class=\"completed-input\">{}</span></div>",
+                    text_to_html(prompt),
+                    escape_html(answer)
+                )
+            }
+            OutputItem::InputResponse { .. } => continue,
+            OutputItem::Table(data) => html_table(data),
+            OutputItem::Plot { svg, .. } => format!("<div class=\"plot\">{}</div>", svg),
+            OutputItem::Image { blob, width, height, caption, .. } => html_image(blob, *width, *height, caption.as_deref()),
+            OutputItem::Error { message } => {
+                format!("<div class=\"program-error\">❌ Error: {}</div>", escape_html(message))
+            }
+            OutputItem::Panic(report) => {
+                let mut block = format!("<details class=\"panic-block\" open><summary>💥 Panic: {}</summary>", escape_html(&report.message));
+                if let Some(location) = &report.location {
+                    block.push_str(&format!("<div class=\"panic-location\">at {}</div>", escape_html(location)));
+                }
+                if let Some(backtrace) = &report.backtrace {
+                    block.push_str(&format!("<pre class=\"panic-backtrace\">{}</pre>", escape_html(backtrace)));
+                }
+                block.push_str("</details>");
+                block
+            }
+        };
+        body.push_str(&line);
+        body.push('\n');
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n  <meta charset=\"UTF-8\">\n  <title>webrust session</title>\n  <style>\n{}\n#terminal {{ height: auto; overflow: visible; }}\n  </style>\n  {}\n</head>\n<body>\n<div id=\"terminal\">\n{}</div>\n</body>\n</html>\n",
+        include_str!("../static/style.css"),
+        standalone_math_head(),
+        body
+    )
+}
+
+fn html_image(blob: &str, width: Option<u32>, height: Option<u32>, caption: Option<&str>) -> String {
+    let mut html = format!(
+        "<figure class=\"image\"><img src=\"{}\" alt=\"{}\"",
+        data_uri(blob).unwrap_or_default(),
+        escape_html(caption.unwrap_or("image"))
+    );
+    if let Some(width) = width {
+        html.push_str(&format!(" width=\"{}\"", width));
+    }
+    if let Some(height) = height {
+        html.push_str(&format!(" height=\"{}\"", height));
+    }
+    html.push('>');
+    if let Some(caption) = caption {
+        html.push_str(&format!("<figcaption>{}</figcaption>", escape_html(caption)));
+    }
+    html.push_str("</figure>");
+    html
+}
+
+fn html_table(data: &TableData) -> String {
+    let align = |index: usize| match data.align.get(index) {
+        Some(Align::Center) => "center",
+        Some(Align::Right) => "right",
+        _ => "left",
+    };
+    let mut html = String::from("<div class=\"data-table-wrapper\"><table class=\"data-table\"><thead><tr>");
+    for (index, header) in data.headers.iter().enumerate() {
+        html.push_str(&format!("<th style=\"text-align: {}\">{}</th>", align(index), header));
+    }
+    html.push_str("</tr></thead><tbody>");
+    for row in &data.rows {
+        html.push_str("<tr>");
+        for (index, cell) in row.iter().enumerate() {
+            html.push_str(&format!("<td style=\"text-align: {}\">{}</td>", align(index), cell.html));
+        }
+        html.push_str("</tr>");
+    }
+    html.push_str("</tbody></table></div>");
+    html
+}
+
+/// Renders output records as Markdown.
+pub(crate) fn render_markdown(items: &[OutputItem]) -> String {
+    let answers = responses(items);
+    let mut blocks: Vec<String> = Vec::new();
+    // Lignes consécutives d'un même paragraphe, séparées par des retours forcés
+    let mut paragraph: Vec<String> = Vec::new();
+    let flush = |paragraph: &mut Vec<String>, blocks: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            blocks.push(paragraph.join("  \n"));
+            paragraph.clear();
+        }
+    };
+
+    for item in items {
+        match item {
+            OutputItem::Html { html } => paragraph.extend(html_to_markdown(html).lines().map(str::to_string)),
+            OutputItem::Text { text } => paragraph.extend(text.lines().map(escape_markdown)),
+            OutputItem::Latex { formula, display: false } => paragraph.push(format!("${}$", formula.trim())),
+            OutputItem::InputRequest { id, prompt, .. } => {
+                let answer = answers.get(id.as_str()).copied().unwrap_or("");
+                let prompt = escape_markdown(&strip_html(&process_styles(prompt)));
+                paragraph.push(match answer {
+                    "" => prompt,
+                    answer => format!("{} **{}**", prompt, escape_markdown(answer)),
+                });
+            }
+            OutputItem::InputResponse { .. } => {}
+            block => {
+                flush(&mut paragraph, &mut blocks);
+                blocks.push(markdown_block(block));
+            }
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    let mut markdown = blocks.join("\n\n");
+    markdown.push('\n');
+    markdown
+}
+
+fn markdown_block(item: &OutputItem) -> String {
+    match item {
+        OutputItem::Latex { formula, .. } => format!("$$\n{}\n$$", formula.trim()),
+        OutputItem::Table(data) => markdown_table(data),
+        OutputItem::Plot { title, svg } => {
+            format!("![{}](data:image/svg+xml,{})", escape_markdown(title.as_deref().unwrap_or("chart")), percent_encode(svg))
+        }
+        OutputItem::Image { blob, caption, .. } => {
+            let alt = escape_markdown(caption.as_deref().unwrap_or("image"));
+            let image = format!("![{}]({})", alt, data_uri(blob).unwrap_or_default());
+            match caption {
+                Some(_) => format!("{}  \n*{}*", image, alt),
+                None => image,
+            }
+        }
+        OutputItem::Error { message } => format!("> ❌ Error: {}", escape_markdown(message)),
+        OutputItem::Panic(report) => {
+            let mut block = format!("> 💥 Panic: {}", escape_markdown(&report.message));
+            if let Some(location) = &report.location {
+                block.push_str(&format!("  \n> at {}", escape_markdown(location)));
+            }
+            if let Some(backtrace) = &report.backtrace {
+                block.push_str(&format!("\n\n```text\n{}\n```", backtrace.trim_end()));
+            }
+            block
+        }
+        _ => String::new(),
+    }
+}
+
+fn markdown_table(data: &TableData) -> String {
+    let cell = |html: &str| escape_markdown(&strip_html(html)).replace('|', "\\|").replace('\n', " ");
+    let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let mut lines = vec![row(data.headers.iter().map(|header| cell(header)).collect())];
+    lines.push(row(data
+        .align
+        .iter()
+        .map(|align| match align {
+            Align::Left => ":---",
+            Align::Center => ":---:",
+            Align::Right => "---:",
+        }.to_string())
+        .collect()));
+    for cells in &data.rows {
+        lines.push(row(cells.iter().map(|c| cell(&c.html)).collect()));
+    }
+    lines.join("\n")
+}
+
+/// Converts styled HTML to Markdown, one output line per line.
+fn html_to_markdown(html: &str) -> String {
+    lazy_static::lazy_static! {
+        static ref SPAN_REGEX: Regex = Regex::new(r#"(?s)<span style="([^"]*)">(.*?)</span>"#).unwrap();
+    }
+    let mut markdown = String::new();
+    let mut last = 0;
+    for caps in SPAN_REGEX.captures_iter(html) {
+        let span = caps.get(0).unwrap();
+        markdown.push_str(&escape_markdown(&strip_html(&html[last..span.start()])));
+        markdown.push_str(&emphasize(&escape_markdown(&strip_html(&caps[2])), &caps[1]));
+        last = span.end();
+    }
+    markdown.push_str(&escape_markdown(&strip_html(&html[last..])));
+    markdown
+}
+
+/// Wraps `text` in the emphasis matching the CSS of its span, keeping the
+/// surrounding spaces outside the markers.
+fn emphasize(text: &str, style: &str) -> String {
+    let core = text.trim();
+    if core.is_empty() {
+        return text.to_string();
+    }
+    let mut marked = if style.contains("font-weight: bold") && style.contains("font-style: italic") {
+        format!("***{}***", core)
+    } else if style.contains("font-weight: bold") {
+        format!("**{}**", core)
+    } else if style.contains("line-through") {
+        core.to_string()
+    } else {
+        format!("*{}*", core)
+    };
+    if style.contains("line-through") {
+        marked = format!("~~{}~~", marked);
+    }
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len();
+    format!("{}{}{}", &text[..start], marked, &text[end..])
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (index, line) in text.split('\n').enumerate() {
+        if index > 0 {
+            escaped.push('\n');
+        }
+        if line.starts_with(['#', '>', '-', '+', '|']) {
+            escaped.push('\\');
+        }
+        for c in line.chars() {
+            if matches!(c, '\\' | '*' | '_' | '`' | '~' | '[' | ']' | '<') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' | b'=' | b',' | b';' | b'\'' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
@@ -51,6 +51,16 @@
 //! - More explicit than using type annotation
 //! - Useful when the generic version causes inference issues
 //!
+//! ### `input_default<T>(prompt: &str, default: T) -> T`
+//!
+//! Pre-fills the field with `default`; an empty answer also returns it.
+//! [`Input::placeholder`] shows a hint in an empty field instead.
+//!
+//! Text fields remember the values submitted to the same prompt: the up/down
+//! arrows walk through them. The history is kept by the server
+//! (`GuiState::input_history`) and sent with each request, so it survives a
+//! page reload.
+//!
 //! ### `try_input<T>(prompt: &str) -> Result<T, T::Err>`
 //!
 //! Non-blocking input function that:
@@ -58,6 +68,26 @@
 //! - Allows custom error handling by the caller
 //! - Useful for advanced validation scenarios
 //!
+//! ## Widgets
+//!
+//! Besides text fields, the web interface renders dedicated widgets. Each one
+//! is described by an [`InputKind`] sent with the request and validated on the
+//! server before the value reaches the program:
+//!
+//! ```rust,no_run
+//! use webrust::input::*;
+//!
+//! let color = select("Favourite color:", &["red", "green", "blue"]); // index
+//! let go_on = confirm("Continue?");                                  // Yes / No buttons
+//! let volume = slider::<f64>("Volume:", 0.0, 1.0, 0.1);
+//! let secret = password("Password:");                                // masked in the page
+//! let notes = textarea("Notes:");                                    // multi-line
+//! let day = date("Start date:");                                     // "YYYY-MM-DD"
+//! ```
+//!
+//! The terminal backend asks for the same values on stdin: a number for
+//! `select`, y/n for `confirm`, and an empty line ends a `textarea`.
+//!
 //! ## Validation Process
 //!
 //! The validation happens in two stages:
@@ -72,10 +102,13 @@
 //!
 //! ### 2. Server-Side Validation (Rust)
 //!
-//! The `validate_input_rust` function:
-//! 1. Receives the type name and input value
-//! 2. Attempts parsing using the appropriate `FromStr` implementation
-//! 3. Returns detailed error messages for common parsing failures
+//! Each request stores a [`Validator`] built by [`parse_validator`]:
+//! 1. A type-erased closure calling `T::from_str` on the submitted value
+//! 2. Works for every `FromStr` type: `u8`, `usize`, `IpAddr`, your own enums...
+//! 3. Returns the `Display` text of `T::Err` as the error message
+//!
+//! `/api/input` runs the same check, so a value that does not parse never
+//! reaches the program.
 //!
 //! ## Error Messages
 //!
@@ -85,6 +118,9 @@
 //! - **Float**: "invalid float literal" 
 //! - **Boolean**: "provided string was not 'true' or 'false'"
 //! - **Character**: "input too long for character"
+//! - **Unsigned / sized integers**: "number too large to fit in target type"
+//! - **IP address**: "invalid IP address syntax"
+//! - **Custom types**: whatever the `Display` of their `FromStr::Err` says
 //!
 //! ## Integration with F-Strings
 //!
@@ -121,13 +157,154 @@
 //! ## Implementation Notes
 //!
 //! - Uses `create_input_request_typed<T>` from the GUI module
-//! - Type information is extracted using `std::any::type_name::<T>()`
+//! - The type name (from `std::any::type_name::<T>()`) is only sent for display;
+//!   parsing is checked by the stored validator
 //! - Validation occurs both client-side (for UX) and server-side (for safety)
 //! - Thread-safe communication via channels between GUI and input threads
 
-use crate::gui::create_input_request_typed;
+use crate::gui::{create_input_request_kind, create_input_request_typed, create_input_request_validated};
+use regex::Regex;
+use serde::Serialize;
+use std::fmt::Display;
 use std::str::FromStr;
+use std::sync::Arc;
+
+/// Checks a submitted value; the error is shown to the user.
+pub type Validator = Box<dyn Fn(&str) -> Result<(), String> + Send>;
 
+/// Receives the answer to an input request.
+pub type Reply = Box<dyn FnOnce(String) + Send>;
+
+/// Builds a validator that parses the value as `T`, reporting `T::Err` on failure.
+pub fn parse_validator<T>() -> Validator
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Box::new(|value: &str| value.trim().parse::<T>().map(|_| ()).map_err(|e| e.to_string()))
+}
+
+/// Describes the widget shown for an input request and how its value is validated.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "widget", rename_all = "snake_case")]
+pub enum InputKind {
+    /// A text field parsed as `value_type` (the last segment of the Rust type name).
+    /// With a `default`, an empty answer is accepted and means the default.
+    /// `value` pre-fills the field and `placeholder` is shown while it is empty.
+    Text {
+        value_type: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        default: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        value: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        placeholder: Option<String>,
+    },
+    /// One button per option; the value is the index of the chosen option.
+    Select { options: Vec<String> },
+    /// Yes / No buttons; the value is `true` or `false`.
+    Confirm,
+    /// A range slider.
+    Slider { min: f64, max: f64, step: f64 },
+    /// A masked text field.
+    Password,
+    /// A multi-line text area.
+    Textarea,
+    /// A date picker; the value is `YYYY-MM-DD`.
+    Date,
+}
+
+impl InputKind {
+    pub fn text(value_type: impl Into<String>) -> Self {
+        InputKind::Text { value_type: value_type.into(), default: None, value: None, placeholder: None }
+    }
+
+    /// Whether submitted values are remembered for the up/down arrows.
+    pub fn keeps_history(&self) -> bool {
+        matches!(self, InputKind::Text { .. })
+    }
+
+    /// A text field for values of type `T`.
+    pub fn for_type<T>() -> Self {
+        let type_name = std::any::type_name::<T>().split("::").last().unwrap_or("String");
+        InputKind::text(type_name)
+    }
+
+    /// The answer as recorded in the page: passwords are masked, choices show
+    /// their label and an empty answer shows the default it stands for.
+    pub fn display_value(&self, value: &str) -> String {
+        match self {
+            InputKind::Password => "••••••".to_string(),
+            InputKind::Select { options } => value.trim().parse::<usize>().ok()
+                .and_then(|index| options.get(index).cloned())
+                .unwrap_or_else(|| value.to_string()),
+            InputKind::Confirm => if value == "true" { "Yes" } else { "No" }.to_string(),
+            InputKind::Text { default: Some(default), .. } if value.trim().is_empty() => default.clone(),
+            _ => value.to_string(),
+        }
+    }
+
+    /// Checks a submitted value against the widget; the parsing of text fields
+    /// is checked by the request's [`Validator`].
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            InputKind::Text { .. } => Ok(()),
+            InputKind::Select { options } => match value.trim().parse::<usize>() {
+                Ok(index) if index < options.len() => Ok(()),
+                _ => Err(format!("Please choose one of the {} options", options.len())),
+            },
+            InputKind::Confirm => match value.trim() {
+                "true" | "false" => Ok(()),
+                _ => Err("Please answer yes or no".to_string()),
+            },
+            InputKind::Slider { min, max, step } => {
+                let number = value.trim().parse::<f64>().map_err(|e| e.to_string())?;
+                if number < *min || number > *max {
+                    return Err(format!("Please enter a value between {} and {}", min, max));
+                }
+                let steps = (number - min) / step;
+                if *step > 0.0 && (steps - steps.round()).abs() > 1e-6 {
+                    return Err(format!("The value must be a multiple of {} from {}", step, min));
+                }
+                Ok(())
+            }
+            InputKind::Password | InputKind::Textarea => Ok(()),
+            InputKind::Date => validate_date(value.trim()),
+        }
+    }
+}
+
+fn validate_date(value: &str) -> Result<(), String> {
+    let invalid = || format!("'{}' is not a valid date (expected YYYY-MM-DD)", value);
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts[..] else { return Err(invalid()) };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return Err(invalid());
+    }
+    let year: u32 = year.parse().map_err(|_| invalid())?;
+    let month: u32 = month.parse().map_err(|_| invalid())?;
+    let day: u32 = day.parse().map_err(|_| invalid())?;
+    let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return Err(invalid()),
+    };
+    if day == 0 || day > days {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+/// Asks for a value of type `T`, asking again until the answer parses.
+///
+/// # Panics
+///
+/// With the terminal backend, panics when stdin is closed before an answer
+/// is read (e.g. `</dev/null` in CI). Feed the answers with a transcript
+/// (`WEBRUST_REPLAY`) or with [`crate::testing::Harness`] instead.
 pub fn input_with_validation<T>(prompt: &str) -> T
 where
     T: FromStr,
@@ -147,9 +324,311 @@ pub fn input_string(prompt: &str) -> String { create_input_request_typed::<Strin
 pub fn try_input<T>(prompt: &str) -> Result<T, T::Err>
 where
     T: FromStr,
+    T::Err: Display,
 {
     let user_input = create_input_request_typed::<T>(prompt);
     user_input.trim().parse()
 }
 
+/// Asks for a value with the field pre-filled with `default`; an empty answer
+/// also gives `default`.
+pub fn input_default<T>(prompt: &str, default: T) -> T
+where
+    T: FromStr + Display + Clone + Send + Sync + 'static,
+    T::Err: Display,
+{
+    Input::new(prompt).prefill(default.clone()).default(default).get()
+}
+
+/// Lets the user pick one of `options` and returns its index.
+///
+/// # Panics
+///
+/// Panics if `options` is empty, since no answer could ever be accepted.
+pub fn select<O: Display>(prompt: &str, options: &[O]) -> usize {
+    assert!(!options.is_empty(), "select('{}') needs at least one option", prompt);
+    let kind = InputKind::Select { options: options.iter().map(|option| option.to_string()).collect() };
+    loop {
+        if let Ok(index) = create_input_request_kind(prompt, kind.clone()).trim().parse() {
+            return index;
+        }
+    }
+}
+
+/// Asks a yes/no question.
+pub fn confirm(prompt: &str) -> bool {
+    create_input_request_kind(prompt, InputKind::Confirm).trim() == "true"
+}
+
+/// Asks for a number between `min` and `max` with a slider.
+///
+/// # Panics
+///
+/// Panics if `min` is greater than `max`, since no answer could ever be accepted.
+pub fn slider<T>(prompt: &str, min: T, max: T, step: T) -> T
+where
+    T: FromStr + Into<f64> + Copy,
+    T::Err: Display,
+{
+    assert!(min.into() <= max.into(), "slider('{}') needs min <= max", prompt);
+    let kind = InputKind::Slider { min: min.into(), max: max.into(), step: step.into() };
+    loop {
+        let value = create_input_request_validated(prompt, kind.clone(), parse_validator::<T>());
+        if let Ok(value) = value.trim().parse() {
+            return value;
+        }
+    }
+}
+
+/// Asks for a secret; the value is never shown in the page.
+pub fn password(prompt: &str) -> String {
+    create_input_request_kind(prompt, InputKind::Password)
+}
+
+/// Asks for multi-line text.
+pub fn textarea(prompt: &str) -> String {
+    create_input_request_kind(prompt, InputKind::Textarea)
+}
+
+/// Asks for a date, returned as `YYYY-MM-DD`.
+pub fn date(prompt: &str) -> String {
+    create_input_request_kind(prompt, InputKind::Date).trim().to_string()
+}
+
+type Check<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// Input with constraints, checked in the browser before the value is accepted.
+///
+/// ```rust,no_run
+/// use webrust::input::Input;
+///
+/// let age = Input::<i32>::new("Age:")
+///     .min(0)
+///     .max(150)
+///     .error_msg("Please enter an age between 0 and 150")
+///     .default(18)
+///     .get();
+///
+/// let name = Input::<String>::new("Username:")
+///     .validate(|name| name.len() <= 16 && name.chars().all(char::is_alphanumeric))
+///     .error_msg("Up to 16 letters or digits")
+///     .get();
+///
+/// let email = Input::<String>::new("Email:")
+///     .pattern(r"^[^@\s]+@[^@\s]+\.[a-z]+$")
+///     .error_msg("This does not look like an email address")
+///     .get();
+/// ```
+pub struct Input<T> {
+    prompt: String,
+    checks: Vec<(Check<T>, String)>,
+    /// The compiled pattern, or why it does not compile.
+    pattern: Option<Result<Regex, String>>,
+    error_msg: Option<String>,
+    default: Option<T>,
+    prefill: Option<T>,
+    placeholder: Option<String>,
+}
+
+impl<T> Input<T>
+where
+    T: FromStr + Display + Clone + Send + Sync + 'static,
+    T::Err: Display,
+{
+    pub fn new(prompt: &str) -> Self {
+        Input {
+            prompt: prompt.to_string(),
+            checks: Vec::new(),
+            pattern: None,
+            error_msg: None,
+            default: None,
+            prefill: None,
+            placeholder: None,
+        }
+    }
+
+    /// Rejects values lower than `min`.
+    pub fn min(mut self, min: T) -> Self
+    where
+        T: PartialOrd,
+    {
+        let message = format!("Please enter a value of at least {}", min);
+        self.checks.push((Box::new(move |value: &T| *value >= min), message));
+        self
+    }
+
+    /// Rejects values greater than `max`.
+    pub fn max(mut self, max: T) -> Self
+    where
+        T: PartialOrd,
+    {
+        let message = format!("Please enter a value of at most {}", max);
+        self.checks.push((Box::new(move |value: &T| *value <= max), message));
+        self
+    }
+
+    /// Requires the text typed by the user to match `pattern`.
+    ///
+    /// An invalid regular expression does not panic: every answer is then
+    /// refused with the compilation error, so the mistake shows in the page.
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(Regex::new(pattern).map_err(|e| format!("Invalid input pattern: {}", e)));
+        self
+    }
+
+    /// Adds a predicate the value must satisfy.
+    pub fn validate(mut self, check: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.checks.push((Box::new(check), "Invalid value".to_string()));
+        self
+    }
+
+    /// Message shown when a constraint fails, instead of the default ones.
+    pub fn error_msg(mut self, message: &str) -> Self {
+        self.error_msg = Some(message.to_string());
+        self
+    }
+
+    /// Value returned when the user submits an empty answer.
+    pub fn default(mut self, value: T) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    /// Pre-fills the field with `value`, which the user can edit.
+    pub fn prefill(mut self, value: T) -> Self {
+        self.prefill = Some(value);
+        self
+    }
+
+    /// Hint shown in the empty field.
+    pub fn placeholder(mut self, text: &str) -> Self {
+        self.placeholder = Some(text.to_string());
+        self
+    }
+
+    /// Checks a submitted value against the type and every constraint.
+    fn check(&self, value: &str) -> Result<(), String> {
+        let value = value.trim();
+        if value.is_empty() && self.default.is_some() {
+            return Ok(());
+        }
+        let fail = |message: &str| Err(self.error_msg.clone().unwrap_or_else(|| message.to_string()));
+        match &self.pattern {
+            Some(Ok(pattern)) if !pattern.is_match(value) => return fail("The value does not have the expected format"),
+            Some(Err(error)) => return Err(error.clone()),
+            _ => {}
+        }
+        let parsed = value.parse::<T>().map_err(|e| e.to_string())?;
+        match self.checks.iter().find(|(check, _)| !check(&parsed)) {
+            Some((_, message)) => fail(message),
+            None => Ok(()),
+        }
+    }
+
+    /// Asks until a value satisfying every constraint is entered.
+    pub fn get(self) -> T {
+        let kind = match InputKind::for_type::<T>() {
+            InputKind::Text { value_type, .. } => InputKind::Text {
+                value_type,
+                default: self.default.as_ref().map(|value| value.to_string()),
+                value: self.prefill.as_ref().map(|value| value.to_string()),
+                placeholder: self.placeholder.clone(),
+            },
+            other => other,
+        };
+        let input = Arc::new(self);
+        loop {
+            let shared = Arc::clone(&input);
+            let validator: Validator = Box::new(move |value: &str| shared.check(value));
+            let answer = create_input_request_validated(&input.prompt, kind.clone(), validator);
+            if answer.trim().is_empty() {
+                if let Some(value) = &input.default {
+                    return value.clone();
+                }
+            }
+            if input.check(&answer).is_ok() {
+                if let Ok(value) = answer.trim().parse::<T>() {
+                    return value;
+                }
+            }
+        }
+    }
+}
+
 pub use input_with_validation as input;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_validator_reports_the_parse_error() {
+        let validator = parse_validator::<u8>();
+        assert_eq!(validator(" 42 "), Ok(()));
+        assert_eq!(validator("256"), Err("number too large to fit in target type".to_string()));
+        assert_eq!(validator("4 2"), Err("invalid digit found in string".to_string()));
+        assert_eq!(validator(""), Err("cannot parse integer from empty string".to_string()));
+    }
+
+    #[test]
+    fn bounds_are_checked_on_both_sides() {
+        let input = Input::<i32>::new("Age:").min(0).max(150);
+        assert_eq!(input.check("0"), Ok(()));
+        assert_eq!(input.check("150"), Ok(()));
+        assert_eq!(input.check("-1"), Err("Please enter a value of at least 0".to_string()));
+        assert_eq!(input.check("151"), Err("Please enter a value of at most 150".to_string()));
+        // Une valeur mal formée échoue sur le type, avant les bornes
+        assert_eq!(input.check("1e3"), Err("invalid digit found in string".to_string()));
+    }
+
+    #[test]
+    fn patterns_and_predicates_use_the_custom_message() {
+        let input = Input::<String>::new("Code:")
+            .pattern(r"^[A-Z]{3}$")
+            .validate(|code| code != "AAA")
+            .error_msg("Three capital letters, not AAA");
+        assert_eq!(input.check("ABC"), Ok(()));
+        assert_eq!(input.check("abc"), Err("Three capital letters, not AAA".to_string()));
+        assert_eq!(input.check("AAA"), Err("Three capital letters, not AAA".to_string()));
+        assert_eq!(Input::<u32>::new("Even:").validate(|n| n % 2 == 0).check("3"), Err("Invalid value".to_string()));
+    }
+
+    #[test]
+    fn an_invalid_pattern_refuses_answers_without_panicking() {
+        let input = Input::<String>::new("Name:").pattern("(unclosed");
+        let error = input.check("anything").unwrap_err();
+        assert!(error.starts_with("Invalid input pattern:"), "{}", error);
+    }
+
+    #[test]
+    fn dates_follow_the_calendar() {
+        for valid in ["2024-02-29", "2000-02-29", "2023-12-31", "2023-01-01", "2023-04-30"] {
+            assert_eq!(validate_date(valid), Ok(()), "{}", valid);
+        }
+        // 2023 et 1900 ne sont pas bissextiles, février n'a jamais 30 jours
+        for invalid in ["2023-02-29", "1900-02-29", "2024-02-30", "2023-04-31", "2023-00-10", "2023-13-01", "2023-01-00"] {
+            assert!(validate_date(invalid).is_err(), "{}", invalid);
+        }
+        for malformed in ["", "2023-1-01", "23-01-01", "2023/01/01", "2023-01-01-01", "yyyy-mm-dd"] {
+            assert!(validate_date(malformed).is_err(), "{}", malformed);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "needs at least one option")]
+    fn select_without_options_panics() {
+        select::<&str>("Pick:", &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "needs min <= max")]
+    fn slider_with_reversed_bounds_panics() {
+        slider("Volume:", 10, 0, 1);
+    }
+
+    #[test]
+    fn an_empty_answer_is_accepted_only_with_a_default() {
+        assert_eq!(Input::<i32>::new("N:").min(1).default(5).check("  "), Ok(()));
+        assert!(Input::<i32>::new("N:").check("").is_err());
+    }
+}
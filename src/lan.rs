@@ -0,0 +1,107 @@
+//! # LAN Module - Following a Run from Other Devices
+//!
+//! In LAN mode the server listens on every network interface, or on the
+//! address given to [`GuiConfig::host`](crate::gui::GuiConfig::host), so
+//! students can follow a live run on their phones. Two links are printed at startup:
+//!
+//! - the **owner** link, opened in the local browser, which answers inputs
+//! - a **read-only** link, also shown as a QR code, which can only watch
+//!
+//! Both carry their own token (see [`crate::access`]); a device without a
+//! link gets nothing.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use webrust::prelude::*;
+//!
+//! #[gui(lan = true)]
+//! fn main() {
+//!     let n: u32 = input("How many terms?");
+//!     let total: u32 = (1..=n).sum();
+//!     println("Sum: {total}");
+//! }
+//! ```
+//!
+//! or `WEBRUST_LAN=1 cargo run`. Holders of the read-only link see the
+//! output and the pending inputs, but the server refuses their answers. A
+//! browser keeps the last link it opened, so test the read-only link in a
+//! private window rather than in the owner's tab.
+//!
+//! ## HTTPS
+//!
+//! On a shared network the tokens travel in clear over plain HTTP. With the
+//! `tls` feature, a PEM certificate and its private key serve the session
+//! over HTTPS instead, through `#[gui(lan = true, tls_cert = "cert.pem", tls_key = "key.pem")]`
+//! or the environment:
+//!
+//! ```bash
+//! WEBRUST_LAN=1 WEBRUST_TLS_CERT=cert.pem WEBRUST_TLS_KEY=key.pem cargo run --features webrust/tls
+//! ```
+//!
+//! A self-signed certificate works once each device accepts the browser
+//! warning:
+//!
+//! ```bash
+//! openssl req -x509 -newkey rsa:2048 -nodes -days 30 -subj "/CN=webrust" -keyout key.pem -out cert.pem
+//! ```
+
+use qrcodegen::{QrCode, QrCodeEcc};
+use std::net::{IpAddr, UdpSocket};
+
+/// Address of the interface used to reach other machines, if any.
+pub(crate) fn lan_address() -> Option<IpAddr> {
+    // Un socket UDP « connecté » n'envoie rien : il indique seulement l'interface choisie
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:9").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified() && !ip.is_loopback()).then_some(ip)
+}
+
+/// `text` as a QR code drawn with block characters, two modules per line.
+///
+/// Dark modules are drawn in black on an explicit white background, with the
+/// 4-module quiet zone scanners expect, whatever the colors of the terminal.
+pub(crate) fn qr_code(text: &str) -> Option<String> {
+    const QUIET_ZONE: i32 = 4;
+    let qr = QrCode::encode_text(text, QrCodeEcc::Low).ok()?;
+    // get_module renvoie false hors du symbole : la zone de silence est claire
+    let dark = |x: i32, y: i32| qr.get_module(x, y);
+    let range = -QUIET_ZONE..qr.size() + QUIET_ZONE;
+    let mut drawing = String::new();
+    for y in range.clone().step_by(2) {
+        drawing.push_str("\x1b[30;107m");
+        for x in range.clone() {
+            drawing.push(match (dark(x, y), y + 1 < range.end && dark(x, y + 1)) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            });
+        }
+        drawing.push_str("\x1b[0m\n");
+    }
+    Some(drawing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn qr_code_has_a_light_quiet_zone_and_dark_modules_filled() {
+        let drawing = qr_code("http://192.168.1.10:8080/?token=0123456789abcdef").unwrap();
+        let qr = QrCode::encode_text("http://192.168.1.10:8080/?token=0123456789abcdef", QrCodeEcc::Low).unwrap();
+        let rows: Vec<String> = drawing
+            .lines()
+            .map(|line| line.trim_start_matches("\x1b[30;107m").trim_end_matches("\x1b[0m").to_string())
+            .collect();
+        let width = (qr.size() + 8) as usize;
+        assert_eq!(rows.len(), (qr.size() as usize + 8).div_ceil(2));
+        assert!(rows.iter().all(|row| row.chars().count() == width));
+        // Deux lignes de texte, soit quatre modules clairs, au-dessus et en dessous
+        assert!(rows[..2].iter().chain(&rows[rows.len() - 2..]).all(|row| row.trim().is_empty()));
+        // Le motif de repérage en haut à gauche commence par un module sombre
+        assert!(matches!(rows[2].chars().nth(4), Some('█' | '▀')));
+    }
+}
@@ -70,7 +70,9 @@
 //! ### Configuration
 //! - **Input**: TeX/LaTeX notation
 //! - **Output**: High-quality HTML/CSS rendering
-//! - **Delimiters**: `$$...$$` for display, `$...$` for inline
+//! - **Delimiters**: optional; a formula wrapped in `\[...\]` or `$$...$$` is
+//!   shown in display mode, one wrapped in `\(...\)` or `$...$` inline; a
+//!   line mixing text and formulas (`$a$ and $b$`) is shown as one formula
 //! - **Processing**: Automatic detection and rendering
 //!
 //! ### Performance
@@ -78,6 +80,27 @@
 //! - **Caching**: MathJax caches rendered expressions
 //! - **Progressive enhancement**: Works without JavaScript (shows raw LaTeX)
 //!
+//! ### Offline Rendering
+//!
+//! MathJax is loaded from a CDN by default. On machines without internet
+//! access, enable the `offline-math` feature: KaTeX is then embedded in the
+//! binary, served from `/vendor/katex/`, and formulas are rendered as MathML
+//! by the browser itself.
+//!
+//! KaTeX's stylesheet and fonts are not bundled, so formulas use the browser's
+//! own math fonts and spacing: they read the same but look plainer than with
+//! MathJax. Macros KaTeX does not know (some `\require` extensions, a few
+//! MathJax-only environments) are shown in red instead of rendered.
+//!
+//! ```toml
+//! webrust = { version = "0.4", features = ["offline-math"] }
+//! ```
+//!
+//! To use another MathJax build (e.g. a local mirror), set its URL with
+//! `#[gui(math_url = "...")]`, [`GuiConfig::math_url`](crate::gui::GuiConfig::math_url)
+//! or the `WEBRUST_MATH_URL` environment variable; it takes precedence over
+//! the bundled renderer.
+//!
 //! ## Supported LaTeX Features
 //!
 //! webrust supports the full range of MathJax/LaTeX features:
@@ -116,6 +139,9 @@
 //!
 //! ### Data Visualization
 //!
+//! For plain data, prefer [`table`](crate::table::table), which renders a
+//! sortable HTML table. A LaTeX `array` is still handy inside formulas:
+//!
 //! // Display a data table using LaTeX array environment
 //! latex("\\begin{array}{|c|c|c|}
 //!     \\hline
@@ -158,25 +184,154 @@
 //! - Consider providing both symbolic and numeric representations
 
 use crate::gui::add_output;
+use crate::output::OutputItem;
+use std::sync::RwLock;
+
+/// MathJax build loaded when no URL is configured and `offline-math` is disabled.
+pub const MATHJAX_CDN_URL: &str = "https://cdn.jsdelivr.net/npm/mathjax@3/es5/tex-mml-chtml.js";
+
+/// KaTeX 0.16.4 (MIT license, see `static/vendor/katex/LICENSE`).
+///
+/// Releases before 0.16.10 are affected by CVE-2024-28243 to CVE-2024-28246;
+/// the file must be replaced by a 0.16.10 or later `katex.min.js`. Meanwhile
+/// formulas are rendered with `trust: false`, which leaves `\includegraphics`
+/// and `\htmlData` inert; the `maxExpand` bypasses remain.
+#[cfg(feature = "offline-math")]
+pub(crate) const KATEX_JS: &[u8] = include_bytes!("../static/vendor/katex/katex.min.js");
+
+/// Renders every `[data-formula]` element once the page is loaded (standalone exports).
+#[cfg(feature = "offline-math")]
+const KATEX_RENDER_ALL: &str = "document.addEventListener('DOMContentLoaded', function () {
+    document.querySelectorAll('[data-formula]').forEach(function (element) {
+      katex.render(element.dataset.formula, element, {
+        displayMode: element.classList.contains('latex-display'), output: 'mathml', throwOnError: false, trust: false
+      });
+    });
+  });";
+
+lazy_static::lazy_static! {
+    static ref MATH_URL: RwLock<Option<String>> = RwLock::new(None);
+}
+
+/// Sets the MathJax URL used by the page and by exports (`None` for the default renderer).
+pub(crate) fn set_math_url(url: Option<String>) {
+    *MATH_URL.write().unwrap() = url;
+}
+
+fn mathjax_head(url: &str) -> String {
+    format!(
+        r#"<script>
+    MathJax = {{
+      tex: {{
+        inlineMath: [['$', '$'], ['\\(', '\\)']],
+        displayMath: [['$$', '$$'], ['\\[', '\\]']],
+        processEscapes: true,
+        processEnvironments: true
+      }},
+      options: {{
+        ignoreHtmlClass: 'tex2jax_ignore',
+        processHtmlClass: 'tex2jax_process'
+      }}
+    }};
+  </script>
+  <script id="MathJax-script" async src="{}"></script>"#,
+        url.replace('"', "%22")
+    )
+}
+
+/// Script tags loading the math renderer in the page.
+pub(crate) fn math_head() -> String {
+    match MATH_URL.read().unwrap().as_deref() {
+        Some(url) => mathjax_head(url),
+        #[cfg(feature = "offline-math")]
+        None => r#"<script src="/vendor/katex/katex.min.js"></script>"#.to_string(),
+        #[cfg(not(feature = "offline-math"))]
+        None => mathjax_head(MATHJAX_CDN_URL),
+    }
+}
+
+/// Script tags for a standalone export: the bundled renderer is inlined.
+pub(crate) fn standalone_math_head() -> String {
+    match MATH_URL.read().unwrap().as_deref() {
+        Some(url) => mathjax_head(url),
+        #[cfg(feature = "offline-math")]
+        None => format!("<script>{}</script>\n  <script>{}</script>", String::from_utf8_lossy(KATEX_JS), KATEX_RENDER_ALL),
+        #[cfg(not(feature = "offline-math"))]
+        None => mathjax_head(MATHJAX_CDN_URL),
+    }
+}
 
 pub fn latex<T: std::fmt::Display>(formula: T) {
-    let latex_content = format!("{}", formula);
-    // Envelopper dans des délimiteurs MathJax
-    let wrapped = if latex_content.contains("\\begin{") || latex_content.contains("\\[") {
-        // Déjà en mode display
-        format!("LATEX_DISPLAY:{}", latex_content)
-    } else {
-        format!("LATEX_INLINE:{}", latex_content)
-    };
-    add_output(wrapped);
+    let formula = format!("{}", formula);
+    let (formula, mode) = split_delimiters(&formula);
+    // Les environnements sont déjà en mode display
+    let display = mode.unwrap_or_else(|| formula.contains("\\begin{"));
+    add_output(OutputItem::Latex { formula, display });
 }
 
 pub fn latex_display<T: std::fmt::Display>(formula: T) {
-    let latex_content = format!("{}", formula);
-    add_output(format!("LATEX_DISPLAY:{}", latex_content));
+    let formula = format!("{}", formula);
+    add_output(OutputItem::Latex { formula: split_delimiters(&formula).0, display: true });
 }
 
 pub fn latex_inline<T: std::fmt::Display>(formula: T) {
-    let latex_content = format!("{}", formula);
-    add_output(format!("LATEX_INLINE:{}", latex_content));
-}
\ No newline at end of file
+    let formula = format!("{}", formula);
+    add_output(OutputItem::Latex { formula: split_delimiters(&formula).0, display: false });
+}
+
+/// Delimiters recognized around formulas, with whether they ask for display mode.
+/// `$$` comes before `$` so that a display formula is not read as two empty ones.
+const DELIMITERS: [(&str, &str, bool); 4] = [("\\[", "\\]", true), ("$$", "$$", true), ("\\(", "\\)", false), ("$", "$", false)];
+
+/// Removes the delimiters of `formula` and tells whether they ask for display mode.
+///
+/// A line mixing text and formulas (`$a$ and $b$`) becomes a single formula
+/// with the text in `\text{}` (`a \text{ and } b`), shown in display mode if
+/// any of its formulas asks for it. Renderers receive the bare formula, so
+/// KaTeX, MathJax and the exports agree.
+fn split_delimiters(formula: &str) -> (String, Option<bool>) {
+    let mut joined = String::new();
+    let mut display = None;
+    let mut rest = formula.trim();
+    while let Some((start, open, close, is_display)) = next_opening(rest) {
+        let Some(length) = rest[start + open.len()..].find(close) else {
+            break;
+        };
+        let inner = &rest[start + open.len()..start + open.len() + length];
+        if inner.trim().is_empty() {
+            break;
+        }
+        push_text(&mut joined, &rest[..start]);
+        joined.push_str(inner.trim());
+        display = Some(display.unwrap_or(false) || is_display);
+        rest = &rest[start + open.len() + length + close.len()..];
+    }
+    if display.is_none() {
+        return (formula.to_string(), None);
+    }
+    push_text(&mut joined, rest);
+    (joined, display)
+}
+
+/// First opening delimiter of `text`, skipping escaped dollars (`\$`).
+fn next_opening(text: &str) -> Option<(usize, &'static str, &'static str, bool)> {
+    let mut escaped = false;
+    for (index, c) in text.char_indices() {
+        if !escaped {
+            if let Some((open, close, display)) = DELIMITERS.into_iter().find(|(open, ..)| text[index..].starts_with(open)) {
+                return Some((index, open, close, display));
+            }
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    None
+}
+
+/// Appends the text between two formulas as `\text{...}`.
+fn push_text(joined: &mut String, text: &str) {
+    if !text.trim().is_empty() {
+        joined.push_str("\\text{");
+        joined.push_str(text);
+        joined.push('}');
+    }
+}
@@ -24,7 +24,7 @@
 //!
 //! ## Example Usage
 //!
-//! ```rust
+//! ```rust,no_run
 //! use webrust::prelude::*;
 //!
 //! #[gui]
@@ -53,26 +53,60 @@
 //! ## Module Organization
 //!
 //! - `gui`: Web server and browser integration
+//! - `backend`: Pluggable output targets (web interface or plain terminal)
+//! - `output`: Structured output records sent to the browser
 //! - `print`: Enhanced printing with styling support
 //! - `input`: Type-safe input handling with validation
 //! - `latex`: Mathematical rendering via MathJax
+//! - `table`: Sortable HTML tables from structs or rows
+//! - `plot`: Line, bar, scatter and histogram charts as inline SVG
+//! - `media`: PNG, JPEG and SVG images served out of band
 //! - `range`: Python-like range generation with fluent syntax
 //! - `enumerate`: Python-style enumeration for iterables
+//! - `access`: Per-run access token and Host/Origin checks of the local server
+//! - `lan`: LAN mode with a read-only link, its QR code and optional HTTPS
+//! - `assets`: Extra images, fonts, stylesheets and scripts served under `/assets/`
+//! - `export`: Session export to standalone HTML and Markdown
+//! - `transcript`: JSON-lines recording and replay of sessions
+//! - `testing`: Harness running programs in tests with fed inputs
+//! - `async_gui`: Non-blocking input and server handle for tokio programs (`async` feature)
 //! - `prelude`: Common imports for ease of use
 
 pub mod gui;
+pub mod backend;
+pub mod output;
 pub mod print;
 pub mod input;
 pub mod latex;
+pub mod table;
+pub mod plot;
+pub mod media;
 pub mod range;
 pub mod enumerate;
+pub mod access;
+pub mod lan;
+pub mod assets;
+pub mod export;
+pub mod transcript;
+pub mod testing;
+#[cfg(feature = "async")]
+pub mod async_gui;
 
 pub mod prelude {
     pub use crate::gui::*;
+    pub use crate::backend::{Backend, BackendKind, TerminalBackend, set_backend};
+    pub use crate::output::OutputItem;
     pub use crate::print::*;
     pub use crate::input::*;
     pub use crate::latex::*;
+    pub use crate::table::*;
+    pub use crate::plot::*;
+    pub use crate::media::*;
     pub use crate::range::*;
     pub use crate::enumerate::*;
+    pub use crate::assets::{add_script, add_stylesheet, register_asset, serve_dir};
+    pub use crate::export::{export_html, export_markdown};
+    #[cfg(feature = "async")]
+    pub use crate::async_gui::*;
     pub use webrust_macros::gui;
 }
\ No newline at end of file
@@ -0,0 +1,290 @@
+//! # Media Module - Images in the Terminal Flow
+//!
+//! This module shows PNG, JPEG, GIF, WebP or SVG images between the other
+//! output lines. Image data is kept out of the page state: each image is
+//! stored once and fetched by the browser from `/api/blob/<id>`, so large
+//! pictures do not slow down state updates.
+//!
+//! ## Quick Display
+//!
+//! ```rust,no_run
+//! use webrust::media::*;
+//!
+//! # let png_bytes: Vec<u8> = Vec::new();
+//! image(&png_bytes, "image/png");
+//! image_file("results/edges.jpg").unwrap();       // type from the extension
+//! svg("<svg xmlns='http://www.w3.org/2000/svg' width='40' height='40'><circle cx='20' cy='20' r='18' fill='#58a6ff'/></svg>");
+//! ```
+//!
+//! ## Size and Caption
+//!
+//! ```rust,no_run
+//! use webrust::media::Image;
+//!
+//! # fn run() -> std::io::Result<()> {
+//! Image::from_file("results/blurred.png")?
+//!     .width(320)
+//!     .caption("Gaussian blur, σ = 2")
+//!     .print();
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Images are scaled down to the width of the terminal. Setting only the width
+//! or the height keeps the aspect ratio. The terminal backend prints a
+//! placeholder line instead of the image.
+//!
+//! ## Formats and Memory
+//!
+//! Only PNG, JPEG, GIF, WebP, AVIF, BMP and SVG are served as images; data of
+//! any other type is sent as `application/octet-stream` and not displayed.
+//! Image URLs are sandboxed, so scripts inside an SVG never run.
+//!
+//! Image data is kept only while the web page may fetch it, and the store is
+//! capped at 64 MiB: a program showing a new frame at every step of a loop
+//! drops the oldest frames, which the page has already loaded. Exports made
+//! later show those frames as empty images.
+
+use crate::assets::{mime_type, Asset, CachePolicy};
+use crate::backend;
+use crate::gui::add_output;
+use crate::output::OutputItem;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tiny_http::Header;
+
+/// Total size of the stored images beyond which the oldest are dropped.
+const BLOB_STORE_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Types served as images; anything else could run as a page of the session.
+const IMAGE_TYPES: [&str; 7] = ["image/png", "image/jpeg", "image/gif", "image/webp", "image/avif", "image/bmp", "image/svg+xml"];
+
+struct Blob {
+    bytes: Arc<[u8]>,
+    mime: String,
+}
+
+#[derive(Default)]
+struct BlobStore {
+    blobs: HashMap<String, Blob>,
+    /// Ids from the least to the most recently shown.
+    order: VecDeque<String>,
+    size: usize,
+}
+
+impl BlobStore {
+    fn insert(&mut self, id: String, blob: impl FnOnce() -> Blob) {
+        if self.blobs.contains_key(&id) {
+            // Réaffichée : elle redevient la plus récente
+            self.order.retain(|other| *other != id);
+        } else {
+            let blob = blob();
+            self.size += blob.bytes.len();
+            self.blobs.insert(id.clone(), blob);
+        }
+        self.order.push_back(id);
+        // La dernière image reste disponible, même seule au-delà de la limite
+        while self.size > BLOB_STORE_LIMIT && self.order.len() > 1 {
+            if let Some(blob) = self.order.pop_front().and_then(|oldest| self.blobs.remove(&oldest)) {
+                self.size -= blob.bytes.len();
+            }
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    // Adressés par contenu : une image affichée plusieurs fois n'est stockée qu'une fois
+    static ref BLOBS: RwLock<BlobStore> = RwLock::new(BlobStore::default());
+}
+
+/// An image waiting to be displayed, with its display options.
+pub struct Image {
+    bytes: Vec<u8>,
+    mime: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    caption: Option<String>,
+}
+
+impl Image {
+    /// Wraps encoded image data, e.g. PNG bytes with `"image/png"`.
+    ///
+    /// A type other than PNG, JPEG, GIF, WebP, AVIF, BMP or SVG becomes
+    /// `application/octet-stream`.
+    pub fn new(bytes: impl Into<Vec<u8>>, mime: &str) -> Self {
+        let mime = mime.trim().to_ascii_lowercase();
+        Image {
+            bytes: bytes.into(),
+            mime: if IMAGE_TYPES.contains(&mime.as_str()) { mime } else { "application/octet-stream".to_string() },
+            width: None,
+            height: None,
+            caption: None,
+        }
+    }
+
+    /// Reads an image file; the type comes from the extension.
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)?;
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+        Ok(Image::new(bytes, mime_type(name)))
+    }
+
+    /// Wraps SVG markup.
+    pub fn svg(markup: impl Into<String>) -> Self {
+        Image::new(markup.into().into_bytes(), "image/svg+xml")
+    }
+
+    /// Display width in pixels.
+    pub fn width(mut self, pixels: u32) -> Self {
+        self.width = Some(pixels);
+        self
+    }
+
+    /// Display height in pixels.
+    pub fn height(mut self, pixels: u32) -> Self {
+        self.height = Some(pixels);
+        self
+    }
+
+    /// Text shown under the image, also used as its alternative text.
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Returns the output record referring to the image; the data is stored
+    /// when the active backend serves images to the page.
+    pub fn to_item(&self) -> OutputItem {
+        let mut hasher = DefaultHasher::new();
+        self.mime.hash(&mut hasher);
+        self.bytes.hash(&mut hasher);
+        let blob = format!("{:016x}", hasher.finish());
+        if backend::current().serves_images() {
+            BLOBS.write().unwrap().insert(blob.clone(), || Blob {
+                bytes: Arc::from(self.bytes.as_slice()),
+                mime: self.mime.clone(),
+            });
+        }
+        OutputItem::Image {
+            blob,
+            mime: self.mime.clone(),
+            width: self.width,
+            height: self.height,
+            caption: self.caption.clone(),
+        }
+    }
+
+    /// Displays the image.
+    pub fn print(&self) {
+        add_output(self.to_item());
+    }
+}
+
+/// Displays encoded image data of the given MIME type.
+pub fn image(bytes: &[u8], mime: &str) {
+    Image::new(bytes, mime).print();
+}
+
+/// Displays an image file; the type comes from the extension.
+pub fn image_file(path: impl AsRef<Path>) -> io::Result<()> {
+    Image::from_file(path)?.print();
+    Ok(())
+}
+
+/// Displays SVG markup as an image.
+pub fn svg(markup: &str) {
+    Image::svg(markup).print();
+}
+
+/// Data and MIME type of a stored image.
+pub(crate) fn blob_data(id: &str) -> Option<(Arc<[u8]>, String)> {
+    BLOBS.read().unwrap().blobs.get(id).map(|blob| (Arc::clone(&blob.bytes), blob.mime.clone()))
+}
+
+/// Response for `/api/blob/<id>`; the content never changes for a given id.
+pub(crate) fn blob_asset(id: &str) -> Option<Asset> {
+    let (bytes, mime) = blob_data(id)?;
+    let mut asset = Asset::typed(Cow::Owned(mime), Cow::Owned(bytes.to_vec()), CachePolicy::Immutable);
+    asset.etag = format!("\"{}\"", id);
+    Some(asset)
+}
+
+/// Headers of `/api/blob/<id>`: the type is never guessed and an image opened
+/// on its own cannot run scripts with the session's cookie.
+pub(crate) fn blob_headers() -> [Header; 2] {
+    [
+        Header::from_bytes(&b"X-Content-Type-Options"[..], &b"nosniff"[..]).unwrap(),
+        Header::from_bytes(&b"Content-Security-Policy"[..], &b"sandbox; default-src 'none'"[..]).unwrap(),
+    ]
+}
+
+/// `data:` URI of a stored image, for exports that must stand alone.
+pub(crate) fn data_uri(id: &str) -> Option<String> {
+    let (bytes, mime) = blob_data(id)?;
+    Some(format!("data:{};base64,{}", mime, base64(&bytes)))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let triple = chunk.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(triple >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mime_of(image: Image) -> String {
+        match image.to_item() {
+            OutputItem::Image { mime, .. } => mime,
+            other => panic!("not an image: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn only_image_types_are_kept() {
+        assert_eq!(mime_of(Image::new(b"png".to_vec(), " Image/PNG ")), "image/png");
+        assert_eq!(mime_of(Image::svg("<svg/>")), "image/svg+xml");
+        assert_eq!(mime_of(Image::new(b"<script>".to_vec(), "text/html")), "application/octet-stream");
+        assert_eq!(mime_of(Image::new(b"x".to_vec(), "image/svg+xml; charset=utf-8")), "application/octet-stream");
+    }
+
+    #[test]
+    fn oldest_blobs_are_evicted_beyond_the_limit() {
+        let frame = |fill: u8| move || Blob { bytes: Arc::from(vec![fill; BLOB_STORE_LIMIT / 3 + 1]), mime: "image/png".to_string() };
+        let mut store = BlobStore::default();
+        store.insert("a".to_string(), frame(1));
+        store.insert("b".to_string(), frame(2));
+        store.insert("a".to_string(), frame(1));
+        store.insert("c".to_string(), frame(3));
+        // `a` a été réaffichée après `b` : c'est `b` qui part
+        assert!(!store.blobs.contains_key("b"));
+        assert!(store.blobs.contains_key("a") && store.blobs.contains_key("c"));
+        assert_eq!(store.size, 2 * (BLOB_STORE_LIMIT / 3 + 1));
+        assert_eq!(store.order, ["a", "c"]);
+    }
+
+    #[test]
+    fn a_single_blob_larger_than_the_limit_is_kept() {
+        let mut store = BlobStore::default();
+        store.insert("big".to_string(), || Blob { bytes: Arc::from(vec![0; BLOB_STORE_LIMIT + 1]), mime: "image/png".to_string() });
+        assert!(store.blobs.contains_key("big"));
+    }
+}
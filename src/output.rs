@@ -0,0 +1,106 @@
+//! # Output Module - Structured Output Records
+//!
+//! Everything shown in the web terminal is stored as an [`OutputItem`] and sent
+//! to the browser as tagged JSON. The frontend dispatches on the `type` tag, so
+//! user text can never be mistaken for a control record, whatever it starts with.
+//!
+//! ## Wire Format
+//!
+//! ```json
+//! {"type": "html", "html": "<span style=\"color: #51cf66\">OK</span><br>"}
+//! {"type": "text", "text": "shown as is"}
+//! {"type": "latex", "formula": "E = mc^2", "display": false}
+//! {"type": "input_request", "id": "input_1", "prompt": "Age:", "kind": {"widget": "text", "value_type": "i32"}, "history": ["41"]}
+//! {"type": "input_request", "id": "input_2", "prompt": "Volume:", "kind": {"widget": "slider", "min": 0.0, "max": 1.0, "step": 0.1}, "history": []}
+//! {"type": "input_response", "id": "input_1", "value": "42"}
+//! {"type": "table", "headers": ["name"], "align": ["left"], "rows": [[{"html": "Paris", "number": null}]], "max_rows": null, "sortable": true}
+//! {"type": "plot", "title": "Trigonometry", "svg": "<svg ...>...</svg>"}
+//! {"type": "image", "blob": "9c1e0f6a2b7d4e83", "mime": "image/png", "width": 320, "height": null, "caption": "Edges"}
+//! {"type": "error", "message": "ParseIntError { kind: InvalidDigit }"}
+//! {"type": "panic", "message": "...", "location": "src/main.rs:3:1", "backtrace": null}
+//! ```
+//!
+//! Prompts are sent as escaped HTML with their `@(...)` styles applied, so the
+//! page, the exports and the terminal style them alike.
+//!
+//! The web state adds a stable `id` and a `version` to each record (see
+//! [`OutputEntry`](crate::gui::OutputEntry)).
+
+use crate::gui::PanicReport;
+use crate::input::InputKind;
+use crate::print::text_to_html;
+use crate::table::TableData;
+use serde::{Serialize, Serializer};
+
+/// One record of program output.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputItem {
+    /// Plain text, escaped by the frontend.
+    Text { text: String },
+    /// Markup trusted as HTML: escaped text from `print`, or raw markup from `print_html`.
+    Html { html: String },
+    /// A LaTeX formula rendered by MathJax.
+    Latex { formula: String, display: bool },
+    /// A prompt waiting for user input; `kind` selects the widget and `history`
+    /// holds earlier answers to the same prompt. `prompt` keeps its `@(...)`
+    /// styles and is sent as HTML, styled like `print`.
+    InputRequest {
+        id: String,
+        #[serde(serialize_with = "styled_html")]
+        prompt: String,
+        kind: InputKind,
+        history: Vec<String>,
+    },
+    /// The value submitted for the input request with the same `id`.
+    InputResponse { id: String, value: String },
+    /// A table built with the [`table`](crate::table) module.
+    Table(TableData),
+    /// A chart drawn by the [`plot`](crate::plot) module, as inline SVG.
+    Plot { title: Option<String>, svg: String },
+    /// An image from the [`media`](crate::media) module; the data is served at `/api/blob/<blob>`.
+    Image { blob: String, mime: String, width: Option<u32>, height: Option<u32>, caption: Option<String> },
+    /// The error returned by the user function.
+    Error { message: String },
+    /// A panic of the user function.
+    Panic(PanicReport),
+}
+
+impl OutputItem {
+    pub fn text(text: impl Into<String>) -> Self {
+        OutputItem::Text { text: text.into() }
+    }
+
+    pub fn html(html: impl Into<String>) -> Self {
+        OutputItem::Html { html: html.into() }
+    }
+
+    /// Appends `other` to this item when both are HTML; returns whether it did.
+    pub(crate) fn try_append(&mut self, other: &OutputItem) -> bool {
+        match (self, other) {
+            (OutputItem::Html { html }, OutputItem::Html { html: more }) => {
+                html.push_str(more);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Strings passed to `add_output*` are HTML, as they always have been.
+impl From<String> for OutputItem {
+    fn from(html: String) -> Self {
+        OutputItem::Html { html }
+    }
+}
+
+impl From<&str> for OutputItem {
+    fn from(html: &str) -> Self {
+        OutputItem::Html { html: html.to_string() }
+    }
+}
+
+/// Sends `text` as escaped HTML with its `@(...)` styles applied.
+fn styled_html<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&text_to_html(text))
+}
@@ -0,0 +1,490 @@
+//! # Plot Module - Charts from Rust Data
+//!
+//! This module draws line, bar, scatter and histogram charts. The SVG is
+//! generated in Rust, so charts work offline and need no JavaScript library.
+//!
+//! ## Quick Plots
+//!
+//! ```rust,no_run
+//! use webrust::plot::*;
+//!
+//! let squares: Vec<f64> = (0..10).map(|i| (i * i) as f64).collect();
+//! line_plot(&squares);                         // x = 0, 1, 2, ...
+//! scatter_plot(&[(0.5, 1.0), (1.5, 2.2), (2.5, 2.9)]);
+//! bar_plot(&[3.0, 7.0, 5.0]);
+//! histogram(&[1.2, 1.9, 2.1, 2.4, 3.3, 3.5, 3.6], 4);
+//! ```
+//!
+//! ## Charts with Several Series
+//!
+//! ```rust,no_run
+//! use webrust::plot::Chart;
+//!
+//! let xs: Vec<f64> = (0..50).map(|i| i as f64 / 10.0).collect();
+//! let sin: Vec<(f64, f64)> = xs.iter().map(|&x| (x, x.sin())).collect();
+//! let cos: Vec<(f64, f64)> = xs.iter().map(|&x| (x, x.cos())).collect();
+//!
+//! Chart::new()
+//!     .title("Trigonometry")
+//!     .x_label("x")
+//!     .y_label("f(x)")
+//!     .line("sin x", &sin)
+//!     .scatter("cos x", &cos)
+//!     .print();
+//! ```
+//!
+//! Series accept `&[f64]` (plotted against their index) or `&[(f64, f64)]`.
+//! Non-finite values are skipped. A legend is drawn when a series has a label.
+
+use crate::gui::add_output;
+use crate::output::OutputItem;
+use crate::print::escape_html;
+use std::fmt::Write;
+
+const PALETTE: [&str; 8] = [
+    "#58a6ff", "#ff6b6b", "#51cf66", "#ffd43b", "#cc5de8", "#ff922b", "#22b8cf", "#f783ac",
+];
+const AXIS_COLOR: &str = "#8b949e";
+const GRID_COLOR: &str = "#30363d";
+const TEXT_COLOR: &str = "#d4d4d4";
+
+const MARGIN_LEFT: f64 = 64.0;
+const MARGIN_RIGHT: f64 = 32.0;
+const MARGIN_TOP: f64 = 40.0;
+const MARGIN_BOTTOM: f64 = 52.0;
+
+/// Data that can be plotted as a series of `(x, y)` points.
+pub trait IntoPoints {
+    fn into_points(self) -> Vec<(f64, f64)>;
+}
+
+impl IntoPoints for &[f64] {
+    fn into_points(self) -> Vec<(f64, f64)> {
+        self.iter().enumerate().map(|(index, &y)| (index as f64, y)).collect()
+    }
+}
+
+impl IntoPoints for &[(f64, f64)] {
+    fn into_points(self) -> Vec<(f64, f64)> {
+        self.to_vec()
+    }
+}
+
+impl IntoPoints for &Vec<f64> {
+    fn into_points(self) -> Vec<(f64, f64)> {
+        self.as_slice().into_points()
+    }
+}
+
+impl IntoPoints for &Vec<(f64, f64)> {
+    fn into_points(self) -> Vec<(f64, f64)> {
+        self.as_slice().into_points()
+    }
+}
+
+impl<const N: usize> IntoPoints for &[f64; N] {
+    fn into_points(self) -> Vec<(f64, f64)> {
+        self.as_slice().into_points()
+    }
+}
+
+impl<const N: usize> IntoPoints for &[(f64, f64); N] {
+    fn into_points(self) -> Vec<(f64, f64)> {
+        self.as_slice().into_points()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SeriesKind {
+    Line,
+    Scatter,
+    Bar,
+    /// Bars of a fixed width touching each other.
+    Histogram,
+}
+
+#[derive(Clone, Debug)]
+struct Series {
+    label: String,
+    kind: SeriesKind,
+    points: Vec<(f64, f64)>,
+    /// Bin width of a histogram.
+    width: f64,
+}
+
+/// Builder for a chart; call [`Chart::print`] to display it.
+#[derive(Clone, Debug)]
+pub struct Chart {
+    title: Option<String>,
+    x_label: Option<String>,
+    y_label: Option<String>,
+    width: u32,
+    height: u32,
+    series: Vec<Series>,
+}
+
+impl Default for Chart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chart {
+    pub fn new() -> Self {
+        Chart {
+            title: None,
+            x_label: None,
+            y_label: None,
+            width: 640,
+            height: 400,
+            series: Vec::new(),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn x_label(mut self, label: impl Into<String>) -> Self {
+        self.x_label = Some(label.into());
+        self
+    }
+
+    pub fn y_label(mut self, label: impl Into<String>) -> Self {
+        self.y_label = Some(label.into());
+        self
+    }
+
+    /// Sets the size of the chart in pixels (640×400 by default).
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width.max(200);
+        self.height = height.max(150);
+        self
+    }
+
+    /// Adds a series drawn as a polyline.
+    pub fn line(self, label: impl Into<String>, data: impl IntoPoints) -> Self {
+        self.add(label, SeriesKind::Line, data.into_points(), 0.0)
+    }
+
+    /// Adds a series drawn as dots.
+    pub fn scatter(self, label: impl Into<String>, data: impl IntoPoints) -> Self {
+        self.add(label, SeriesKind::Scatter, data.into_points(), 0.0)
+    }
+
+    /// Adds a series drawn as bars; bars of several series are placed side by side.
+    pub fn bar(self, label: impl Into<String>, data: impl IntoPoints) -> Self {
+        self.add(label, SeriesKind::Bar, data.into_points(), 0.0)
+    }
+
+    /// Adds the histogram of `values` split into `bins` equal bins.
+    pub fn histogram(self, label: impl Into<String>, values: &[f64], bins: usize) -> Self {
+        let values: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+        let bins = bins.max(1);
+        let (min, max) = bounds(values.iter().copied()).unwrap_or((0.0, 1.0));
+        let (min, max) = if min == max { (min - 0.5, max + 0.5) } else { (min, max) };
+        let width = (max - min) / bins as f64;
+        let mut counts = vec![0usize; bins];
+        for value in &values {
+            let index = (((value - min) / width) as usize).min(bins - 1);
+            counts[index] += 1;
+        }
+        let points = counts
+            .iter()
+            .enumerate()
+            .map(|(index, &count)| (min + width * (index as f64 + 0.5), count as f64))
+            .collect();
+        self.add(label, SeriesKind::Histogram, points, width)
+    }
+
+    fn add(mut self, label: impl Into<String>, kind: SeriesKind, points: Vec<(f64, f64)>, width: f64) -> Self {
+        let points = points.into_iter().filter(|(x, y)| x.is_finite() && y.is_finite()).collect();
+        self.series.push(Series { label: label.into(), kind, points, width });
+        self
+    }
+
+    /// Spacing used for bars: the smallest gap between two x values of bar series.
+    fn bar_slot(&self) -> f64 {
+        let mut xs: Vec<f64> = self.series
+            .iter()
+            .filter(|series| series.kind == SeriesKind::Bar)
+            .flat_map(|series| series.points.iter().map(|(x, _)| *x))
+            .collect();
+        xs.sort_by(|a, b| a.total_cmp(b));
+        xs.windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .filter(|gap| *gap > 0.0)
+            .fold(None, |min: Option<f64>, gap| Some(min.map_or(gap, |m| m.min(gap))))
+            .unwrap_or(1.0)
+    }
+
+    fn ranges(&self, slot: f64) -> ((f64, f64), (f64, f64)) {
+        let mut xs = Vec::new();
+        let mut ys = Vec::new();
+        let mut has_bars = false;
+        for series in &self.series {
+            let half = match series.kind {
+                SeriesKind::Bar => slot / 2.0,
+                SeriesKind::Histogram => series.width / 2.0,
+                _ => 0.0,
+            };
+            if half > 0.0 {
+                has_bars = true;
+            }
+            for &(x, y) in &series.points {
+                xs.push(x - half);
+                xs.push(x + half);
+                ys.push(y);
+            }
+        }
+        if has_bars {
+            ys.push(0.0);
+        }
+        (pad(widen(bounds(xs.into_iter()))), pad(widen(bounds(ys.into_iter()))))
+    }
+
+    /// Renders the chart as a standalone SVG document.
+    pub fn to_svg(&self) -> String {
+        let width = self.width as f64;
+        let height = self.height as f64;
+        let plot_width = width - MARGIN_LEFT - MARGIN_RIGHT;
+        let plot_height = height - MARGIN_TOP - MARGIN_BOTTOM;
+
+        let slot = self.bar_slot();
+        let ((x_min, x_max), (y_min, y_max)) = self.ranges(slot);
+        let x_ticks = nice_ticks(x_min, x_max, (plot_width / 80.0).max(2.0) as usize);
+        let y_ticks = nice_ticks(y_min, y_max, (plot_height / 50.0).max(2.0) as usize);
+        let (x_min, x_max) = (x_min.min(x_ticks.first), x_max.max(x_ticks.last));
+        let (y_min, y_max) = (y_min.min(y_ticks.first), y_max.max(y_ticks.last));
+
+        let sx = |x: f64| MARGIN_LEFT + (x - x_min) / (x_max - x_min) * plot_width;
+        let sy = |y: f64| MARGIN_TOP + (y_max - y) / (y_max - y_min) * plot_height;
+
+        let mut svg = String::new();
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Arial, sans-serif" font-size="12">"#,
+            w = self.width,
+            h = self.height
+        );
+
+        // Grille et graduations
+        for &tick in &y_ticks.values {
+            let y = sy(tick);
+            let _ = write!(
+                svg,
+                r#"<line x1="{x1:.1}" y1="{y:.1}" x2="{x2:.1}" y2="{y:.1}" stroke="{GRID_COLOR}"/><text x="{tx:.1}" y="{ty:.1}" fill="{TEXT_COLOR}" text-anchor="end">{label}</text>"#,
+                x1 = MARGIN_LEFT,
+                x2 = MARGIN_LEFT + plot_width,
+                tx = MARGIN_LEFT - 6.0,
+                ty = y + 4.0,
+                label = format_tick(tick, y_ticks.step)
+            );
+        }
+        for &tick in &x_ticks.values {
+            let x = sx(tick);
+            let _ = write!(
+                svg,
+                r#"<line x1="{x:.1}" y1="{y1:.1}" x2="{x:.1}" y2="{y2:.1}" stroke="{GRID_COLOR}"/><text x="{x:.1}" y="{ty:.1}" fill="{TEXT_COLOR}" text-anchor="middle">{label}</text>"#,
+                y1 = MARGIN_TOP,
+                y2 = MARGIN_TOP + plot_height,
+                ty = MARGIN_TOP + plot_height + 16.0,
+                label = format_tick(tick, x_ticks.step)
+            );
+        }
+
+        // Séries
+        let bar_series: Vec<usize> = (0..self.series.len()).filter(|&i| self.series[i].kind == SeriesKind::Bar).collect();
+        let bar_width = slot * 0.8 / bar_series.len().max(1) as f64;
+        for (index, series) in self.series.iter().enumerate() {
+            let color = PALETTE[index % PALETTE.len()];
+            match series.kind {
+                SeriesKind::Line => {
+                    let points: Vec<String> = series.points.iter().map(|&(x, y)| format!("{:.1},{:.1}", sx(x), sy(y))).collect();
+                    let _ = write!(svg, r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="2"/>"#, points.join(" "));
+                    if let [(x, y)] = series.points[..] {
+                        let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{color}"/>"#, sx(x), sy(y));
+                    }
+                }
+                SeriesKind::Scatter => {
+                    for &(x, y) in &series.points {
+                        let _ = write!(svg, r#"<circle cx="{:.1}" cy="{:.1}" r="3.5" fill="{color}"/>"#, sx(x), sy(y));
+                    }
+                }
+                SeriesKind::Bar | SeriesKind::Histogram => {
+                    let (bar, offset) = if series.kind == SeriesKind::Bar {
+                        let position = bar_series.iter().position(|&i| i == index).unwrap_or(0) as f64;
+                        (bar_width, -slot * 0.4 + bar_width * position)
+                    } else {
+                        (series.width, -series.width / 2.0)
+                    };
+                    for &(x, y) in &series.points {
+                        let left = sx(x + offset);
+                        let right = sx(x + offset + bar);
+                        let (top, bottom) = (sy(y.max(0.0)), sy(y.min(0.0)));
+                        let _ = write!(
+                            svg,
+                            r#"<rect x="{left:.1}" y="{top:.1}" width="{:.1}" height="{:.1}" fill="{color}" fill-opacity="0.85" stroke="{GRID_COLOR}" stroke-width="0.5"/>"#,
+                            (right - left).max(0.5),
+                            bottom - top
+                        );
+                    }
+                }
+            }
+        }
+
+        // Axes
+        let axis_y = if y_min <= 0.0 && 0.0 <= y_max { sy(0.0) } else { MARGIN_TOP + plot_height };
+        let _ = write!(
+            svg,
+            r#"<line x1="{x1:.1}" y1="{axis_y:.1}" x2="{x2:.1}" y2="{axis_y:.1}" stroke="{AXIS_COLOR}"/><line x1="{x1:.1}" y1="{y1:.1}" x2="{x1:.1}" y2="{y2:.1}" stroke="{AXIS_COLOR}"/>"#,
+            x1 = MARGIN_LEFT,
+            x2 = MARGIN_LEFT + plot_width,
+            y1 = MARGIN_TOP,
+            y2 = MARGIN_TOP + plot_height
+        );
+
+        if let Some(title) = &self.title {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="24" fill="{TEXT_COLOR}" font-size="15" font-weight="bold" text-anchor="middle">{}</text>"#,
+                width / 2.0,
+                escape_html(title)
+            );
+        }
+        if let Some(label) = &self.x_label {
+            let _ = write!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" fill="{TEXT_COLOR}" text-anchor="middle">{}</text>"#,
+                MARGIN_LEFT + plot_width / 2.0,
+                height - 10.0,
+                escape_html(label)
+            );
+        }
+        if let Some(label) = &self.y_label {
+            let y = MARGIN_TOP + plot_height / 2.0;
+            let _ = write!(
+                svg,
+                r#"<text x="16" y="{y:.1}" fill="{TEXT_COLOR}" text-anchor="middle" transform="rotate(-90 16 {y:.1})">{}</text>"#,
+                escape_html(label)
+            );
+        }
+
+        // Légende
+        let labelled: Vec<(usize, &Series)> = self.series.iter().enumerate().filter(|(_, series)| !series.label.is_empty()).collect();
+        if !labelled.is_empty() {
+            let longest = labelled.iter().map(|(_, series)| series.label.chars().count()).max().unwrap_or(0) as f64;
+            let box_width = 34.0 + longest * 7.0;
+            let left = MARGIN_LEFT + plot_width - box_width - 8.0;
+            let _ = write!(
+                svg,
+                r##"<rect x="{left:.1}" y="{:.1}" width="{box_width:.1}" height="{:.1}" fill="#0d1117" fill-opacity="0.85" stroke="{GRID_COLOR}"/>"##,
+                MARGIN_TOP + 8.0,
+                labelled.len() as f64 * 18.0 + 8.0
+            );
+            for (row, (index, series)) in labelled.iter().enumerate() {
+                let y = MARGIN_TOP + 22.0 + row as f64 * 18.0;
+                let color = PALETTE[index % PALETTE.len()];
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{:.1}" y="{:.1}" width="14" height="8" fill="{color}"/><text x="{:.1}" y="{:.1}" fill="{TEXT_COLOR}">{}</text>"#,
+                    left + 8.0,
+                    y - 8.0,
+                    left + 28.0,
+                    y,
+                    escape_html(&series.label)
+                );
+            }
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Displays the chart.
+    pub fn print(&self) {
+        add_output(OutputItem::Plot { title: self.title.clone(), svg: self.to_svg() });
+    }
+}
+
+struct Ticks {
+    values: Vec<f64>,
+    first: f64,
+    last: f64,
+    step: f64,
+}
+
+fn bounds(values: impl Iterator<Item = f64>) -> Option<(f64, f64)> {
+    values.fold(None, |range, value| match range {
+        None => Some((value, value)),
+        Some((min, max)) => Some((min.min(value), max.max(value))),
+    })
+}
+
+/// Makes sure the range is not empty.
+fn widen(range: Option<(f64, f64)>) -> (f64, f64) {
+    match range {
+        None => (0.0, 1.0),
+        Some((min, max)) if min == max => {
+            let pad = if min == 0.0 { 1.0 } else { min.abs() * 0.1 };
+            (min - pad, max + pad)
+        }
+        Some(range) => range,
+    }
+}
+
+/// Leaves a small margin around the data, except at a zero baseline.
+fn pad((min, max): (f64, f64)) -> (f64, f64) {
+    let margin = (max - min) * 0.04;
+    let min = if min == 0.0 { min } else { min - margin };
+    let max = if max == 0.0 { max } else { max + margin };
+    (min, max)
+}
+
+/// Picks round tick values (1, 2 or 5 times a power of ten) covering `min..=max`.
+fn nice_ticks(min: f64, max: f64, count: usize) -> Ticks {
+    let raw = (max - min) / count.max(1) as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= raw)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).floor() * step;
+    let last = (max / step).ceil() * step;
+    let count = ((last - first) / step).round() as usize;
+    let values = (0..=count).map(|i| first + step * i as f64).collect();
+    Ticks { values, first, last, step }
+}
+
+fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let text = format!("{:.*}", decimals, value);
+    // Évite l'affichage de « -0 »
+    if text.trim_start_matches('-').chars().all(|c| c == '0' || c == '.') {
+        text.trim_start_matches('-').to_string()
+    } else {
+        text
+    }
+}
+
+/// Draws `data` as a line chart.
+pub fn line_plot(data: impl IntoPoints) {
+    Chart::new().line("", data).print();
+}
+
+/// Draws `data` as a scatter plot.
+pub fn scatter_plot(data: impl IntoPoints) {
+    Chart::new().scatter("", data).print();
+}
+
+/// Draws `data` as a bar chart.
+pub fn bar_plot(data: impl IntoPoints) {
+    Chart::new().bar("", data).print();
+}
+
+/// Draws the histogram of `values` with `bins` bins.
+pub fn histogram(values: &[f64], bins: usize) {
+    Chart::new().histogram("", values, bins).print();
+}
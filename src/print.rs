@@ -26,6 +26,13 @@
 //!
 //! ## Implementation Details
 //!
+//! ### Escaping
+//!
+//! Text passed to `print`/`println` is HTML-escaped (`&`, `<`, `>`, `"`, `'`)
+//! before the styling markers are turned into spans, so values such as
+//! `<img onerror=...>` or `Vec<Option<&str>>` show up literally. Use
+//! `print_html`/`println_html` for intentional markup.
+//!
 //! ### `process_styles(text: &str) -> String`
 //!
 //! The core styling processor that:
@@ -56,6 +63,11 @@
 //! - Same as `print_str` but adds line break
 //! - Most commonly used printing function
 //!
+//! ### `print_html` / `println_html`
+//!
+//! Same as above but the text is trusted as HTML: it is not escaped, only the
+//! styling markers are processed. Never pass untrusted input to these.
+//!
 //! ## Color Mapping
 //!
 //! Colors are mapped to web-safe, dark-theme friendly values:
@@ -82,13 +94,14 @@
 //! println("Status: @(green)OK@(reset), Code: @(blue)200@(reset)");
 //!
 
-use crate::gui::{add_output_same_line, add_output_new_line};
+use crate::backend;
 use regex::Regex;
 
-fn process_styles(text: &str) -> String {
-    lazy_static::lazy_static! { 
-        static ref STYLE_REGEX: Regex = Regex::new(r"@\(([^)]*)\)([^@]*)").unwrap();
-    }
+lazy_static::lazy_static! {
+    static ref STYLE_REGEX: Regex = Regex::new(r"@\(([^)]*)\)([^@]*)").unwrap();
+}
+
+pub(crate) fn process_styles(text: &str) -> String {
     let mut result = text.to_string();
     result = STYLE_REGEX.replace_all(&result, |caps: &regex::Captures| {
         let styles = &caps[1];
@@ -140,6 +153,59 @@ fn process_styles(text: &str) -> String {
     preserve_formatting(&result)
 }
 
+/// Terminal counterpart of `process_styles`: converts style markers to ANSI escape codes.
+/// Styles are dropped entirely when the `NO_COLOR` environment variable is set.
+pub(crate) fn process_styles_ansi(text: &str) -> String {
+    styles_to_ansi(text, std::env::var_os("NO_COLOR").is_none())
+}
+
+/// Converts style markers to ANSI codes, or only removes them without `use_color`.
+pub(crate) fn styles_to_ansi(text: &str, use_color: bool) -> String {
+    STYLE_REGEX.replace_all(text, |caps: &regex::Captures| {
+        let styles = &caps[1];
+        let content = &caps[2];
+        let mut codes = Vec::new();
+        for style in styles.split(',').map(|s| s.trim()) {
+            match style {
+                "black" => codes.push("30"),
+                "red" => codes.push("31"),
+                "green" => codes.push("32"),
+                "yellow" => codes.push("33"),
+                "blue" => codes.push("34"),
+                "magenta" => codes.push("35"),
+                "cyan" => codes.push("36"),
+                "white" => codes.push("37"),
+                "gray" | "grey" => codes.push("90"),
+                "orange" => codes.push("38;5;208"),
+                "purple" => codes.push("38;5;141"),
+                "pink" => codes.push("38;5;211"),
+                "bright_cyan" => codes.push("96"),
+
+                "bold" => codes.push("1"),
+                "italic" => codes.push("3"),
+                "underline" => codes.push("4"),
+                "strike" => codes.push("9"),
+
+                "bg-black" => codes.push("40"),
+                "bg-red" => codes.push("41"),
+                "bg-green" => codes.push("42"),
+                "bg-yellow" => codes.push("43"),
+                "bg-blue" => codes.push("44"),
+                "bg-magenta" => codes.push("45"),
+                "bg-cyan" => codes.push("46"),
+                "bg-white" => codes.push("47"),
+                "bg-gray" | "bg-grey" => codes.push("100"),
+                _ => {}
+            }
+        }
+        if codes.is_empty() || !use_color {
+            content.to_string()
+        } else {
+            format!("\x1b[{}m{}\x1b[0m", codes.join(";"), content)
+        }
+    }).to_string()
+}
+
 fn preserve_formatting(text: &str) -> String {
     if text.contains('\n') && (text.contains('{') || text.contains('[')) {
         format!("<pre style=\"font-family: 'Courier New', monospace; margin: 0; display: inline;\">{}</pre>", text)
@@ -148,14 +214,43 @@ fn preserve_formatting(text: &str) -> String {
     }
 }
 
+/// Escapes the characters that are significant in HTML.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Turns user text into HTML: escapes it, then applies the `@(...)` spans.
+pub(crate) fn text_to_html(text: &str) -> String {
+    process_styles(&escape_html(text))
+}
+
 pub fn print_str<T: std::fmt::Display>(text: T) {
-    let processed = process_styles(&format!("{}", text));
-    add_output_same_line(processed);
+    backend::current().print(&format!("{}", text), false);
 }
 
 pub fn println_str<T: std::fmt::Display>(text: T) {
-    let processed = process_styles(&format!("{}", text));
-    add_output_new_line(format!("{}<br>", processed));
+    backend::current().print(&format!("{}", text), true);
+}
+
+/// Prints raw HTML without a trailing newline. The markup is not escaped.
+pub fn print_html<T: std::fmt::Display>(html: T) {
+    backend::current().print_html(&format!("{}", html), false);
+}
+
+/// Prints raw HTML followed by a line break. The markup is not escaped.
+pub fn println_html<T: std::fmt::Display>(html: T) {
+    backend::current().print_html(&format!("{}", html), true);
 }
 
 pub use print_str as print;
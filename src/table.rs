@@ -0,0 +1,315 @@
+//! # Table Module - Tabular Data Display
+//!
+//! This module renders data as styled HTML tables in the web terminal. Columns
+//! are sortable by clicking their header, numbers are right-aligned, and long
+//! tables can be collapsed behind a "Show more" button.
+//!
+//! ## Usage
+//!
+//! ### From Serializable Values
+//!
+//! Any slice of `T: Serialize` works. Struct fields (or map keys) become the
+//! column headers, in declaration order:
+//!
+//! ```rust,no_run
+//! use serde::Serialize;
+//! use webrust::table::table;
+//!
+//! #[derive(Serialize)]
+//! struct City { name: &'static str, population: u32, area: f64 }
+//!
+//! let cities = vec![
+//!     City { name: "Paris", population: 2_102_650, area: 105.4 },
+//!     City { name: "Lyon", population: 522_250, area: 47.87 },
+//! ];
+//! table(&cities);
+//! ```
+//!
+//! ### From Rows
+//!
+//! ```rust,no_run
+//! use webrust::table::Table;
+//!
+//! Table::from_rows(
+//!     &["Index", "Value", "Status"],
+//!     vec![
+//!         vec![1.to_string(), "10".to_string(), "@(green)ok".to_string()],
+//!         vec![2.to_string(), "20".to_string(), "@(red)failed".to_string()],
+//!     ],
+//! ).print();
+//! ```
+//!
+//! ### Options
+//!
+//! ```rust,no_run
+//! use webrust::table::{Align, Table};
+//! # let data: Vec<(String, f64)> = Vec::new();
+//!
+//! Table::new(&data)
+//!     .headers(&["Name", "Score"])
+//!     .align("Name", Align::Center)
+//!     .precision(2)
+//!     .max_rows(20)
+//!     .print();
+//! ```
+//!
+//! ## Cells
+//!
+//! Cell text is escaped like `print` output and may contain `@(style)` markers.
+//! Numeric cells are sorted numerically, everything else alphabetically.
+//! Nested values (arrays, objects) are shown as compact JSON.
+
+use crate::gui::add_output;
+use crate::output::OutputItem;
+use crate::print::text_to_html;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Display;
+
+/// Horizontal alignment of a column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// One cell as sent to the browser.
+#[derive(Clone, Debug, Serialize)]
+pub struct TableCell {
+    /// Escaped and styled content.
+    pub html: String,
+    /// Numeric value used for sorting, when the cell is a number.
+    pub number: Option<f64>,
+}
+
+/// The table record stored in the output buffer.
+#[derive(Clone, Debug, Serialize)]
+pub struct TableData {
+    pub headers: Vec<String>,
+    pub align: Vec<Align>,
+    pub rows: Vec<Vec<TableCell>>,
+    pub max_rows: Option<usize>,
+    pub sortable: bool,
+}
+
+#[derive(Clone)]
+struct Cell {
+    text: String,
+    number: Option<f64>,
+    integer: bool,
+}
+
+impl Cell {
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::Null => Cell::text(String::new()),
+            Value::String(text) => Cell::text(text.clone()),
+            Value::Number(number) => Cell {
+                text: number.to_string(),
+                number: number.as_f64(),
+                integer: !number.is_f64(),
+            },
+            other => Cell::text(other.to_string()),
+        }
+    }
+
+    fn from_display(value: impl Display) -> Self {
+        let text = value.to_string();
+        match text.trim().parse::<f64>() {
+            Ok(number) if number.is_finite() => Cell {
+                integer: text.trim().parse::<i64>().is_ok(),
+                number: Some(number),
+                text,
+            },
+            _ => Cell::text(text),
+        }
+    }
+
+    fn text(text: String) -> Self {
+        Cell { text, number: None, integer: false }
+    }
+}
+
+/// Builder for a table; call [`Table::print`] to display it.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<Cell>>,
+    align: Vec<(String, Align)>,
+    precision: Option<usize>,
+    number_format: Option<Box<dyn Fn(f64) -> String>>,
+    max_rows: Option<usize>,
+    sortable: bool,
+}
+
+impl Table {
+    /// Builds a table from serializable values (structs, maps, tuples or scalars).
+    pub fn new<T: Serialize>(items: &[T]) -> Self {
+        let values: Vec<Value> = items
+            .iter()
+            .map(|item| serde_json::to_value(item).unwrap_or(Value::Null))
+            .collect();
+
+        let mut headers: Vec<String> = Vec::new();
+        for value in &values {
+            match value {
+                Value::Object(map) => {
+                    for key in map.keys() {
+                        if !headers.contains(key) {
+                            headers.push(key.clone());
+                        }
+                    }
+                }
+                Value::Array(items) => {
+                    for index in headers.len()..items.len() {
+                        headers.push(index.to_string());
+                    }
+                }
+                _ => {
+                    if headers.is_empty() {
+                        headers.push("value".to_string());
+                    }
+                }
+            }
+        }
+
+        let rows = values
+            .iter()
+            .map(|value| match value {
+                Value::Object(map) => headers
+                    .iter()
+                    .map(|header| Cell::from_value(map.get(header).unwrap_or(&Value::Null)))
+                    .collect(),
+                Value::Array(items) => items.iter().map(Cell::from_value).collect(),
+                other => vec![Cell::from_value(other)],
+            })
+            .collect();
+
+        Table::with(headers, rows)
+    }
+
+    /// Builds a table from headers and rows of displayable values.
+    pub fn from_rows<H: Display, C: Display>(headers: &[H], rows: Vec<Vec<C>>) -> Self {
+        let headers = headers.iter().map(|header| header.to_string()).collect();
+        let rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(Cell::from_display).collect())
+            .collect();
+        Table::with(headers, rows)
+    }
+
+    fn with(headers: Vec<String>, rows: Vec<Vec<Cell>>) -> Self {
+        Table {
+            headers,
+            rows,
+            align: Vec::new(),
+            precision: None,
+            number_format: None,
+            max_rows: None,
+            sortable: true,
+        }
+    }
+
+    /// Replaces the column headers.
+    pub fn headers<H: Display>(mut self, headers: &[H]) -> Self {
+        self.headers = headers.iter().map(|header| header.to_string()).collect();
+        self
+    }
+
+    /// Sets the alignment of a column, by header name.
+    /// Numbers are right-aligned and text left-aligned by default.
+    pub fn align(mut self, column: &str, align: Align) -> Self {
+        self.align.push((column.to_string(), align));
+        self
+    }
+
+    /// Shows non-integer numbers with `digits` decimals.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    /// Formats every numeric cell with `format`; takes precedence over `precision`.
+    pub fn number_format(mut self, format: impl Fn(f64) -> String + 'static) -> Self {
+        self.number_format = Some(Box::new(format));
+        self
+    }
+
+    /// Shows only the first `rows` rows, with a button revealing the rest.
+    pub fn max_rows(mut self, rows: usize) -> Self {
+        self.max_rows = Some(rows);
+        self
+    }
+
+    /// Enables or disables sorting by clicking the headers (enabled by default).
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    fn format_cell(&self, cell: &Cell) -> String {
+        match (cell.number, &self.number_format, self.precision) {
+            (Some(number), Some(format), _) => format(number),
+            (Some(number), None, Some(digits)) if !cell.integer => format!("{:.*}", digits, number),
+            _ => cell.text.clone(),
+        }
+    }
+
+    fn column_align(&self, index: usize) -> Align {
+        let explicit = self.headers.get(index).and_then(|header| {
+            self.align.iter().rev().find(|(column, _)| column == header).map(|(_, align)| *align)
+        });
+        explicit.unwrap_or_else(|| {
+            let mut cells = self.rows.iter().filter_map(|row| row.get(index)).filter(|cell| !cell.text.is_empty());
+            let mut any = false;
+            let numeric = cells.all(|cell| {
+                any = true;
+                cell.number.is_some()
+            });
+            if any && numeric { Align::Right } else { Align::Left }
+        })
+    }
+
+    /// Converts the table into its output record.
+    pub fn to_data(&self) -> TableData {
+        let columns = self.rows.iter().map(Vec::len).chain([self.headers.len()]).max().unwrap_or(0);
+        let mut headers = self.headers.clone();
+        for index in headers.len()..columns {
+            headers.push(index.to_string());
+        }
+        let rows = self.rows
+            .iter()
+            .map(|row| {
+                (0..columns)
+                    .map(|index| match row.get(index) {
+                        Some(cell) => TableCell { html: text_to_html(&self.format_cell(cell)), number: cell.number },
+                        None => TableCell { html: String::new(), number: None },
+                    })
+                    .collect()
+            })
+            .collect();
+        TableData {
+            align: (0..columns).map(|index| self.column_align(index)).collect(),
+            headers: headers.iter().map(|header| text_to_html(header)).collect(),
+            rows,
+            max_rows: self.max_rows,
+            sortable: self.sortable,
+        }
+    }
+
+    /// Displays the table.
+    pub fn print(&self) {
+        add_output(OutputItem::Table(self.to_data()));
+    }
+}
+
+/// Displays a slice of serializable values as a table.
+pub fn table<T: Serialize>(items: &[T]) {
+    Table::new(items).print();
+}
+
+/// Displays rows of displayable values under the given headers.
+pub fn table_rows<H: Display, C: Display>(headers: &[H], rows: Vec<Vec<C>>) {
+    Table::from_rows(headers, rows).print();
+}
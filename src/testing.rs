@@ -0,0 +1,306 @@
+//! # Testing Module - Running Programs Without a Browser
+//!
+//! This module runs a webrust program inside a test: inputs are answered from
+//! a queue, output is kept in memory, and no server is started. Each run has
+//! its own state, so tests can run in parallel.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use webrust::prelude::*;
+//! use webrust::testing::Harness;
+//!
+//! #[gui]
+//! fn greet() {
+//!     let name: String = input("Name:");
+//!     let age: u32 = input("Age:");
+//!     println("Hello @(green){name}@(reset), born around {2025 - age}");
+//!     latex("E = mc^2");
+//! }
+//!
+//! let mut harness = Harness::new();
+//! harness.feed("Ada").feed("forty").feed("40");
+//! let run = harness.run(greet);
+//!
+//! run.assert_contains("Hello Ada, born around 1985");
+//! run.assert_styled("Ada", "green");
+//! run.assert_latex("E = mc^2");
+//! assert_eq!(run.rejections()[0].value, "forty");
+//! ```
+//!
+//! ## Inputs
+//!
+//! Fed values are typed answers, as in the terminal backend: a select accepts
+//! the option name or its 1-based number, a confirm accepts `y` or `n`. A value
+//! rejected by the program's validation is recorded in
+//! [`TestRun::rejections`] and the next value is tried, like a user typing
+//! again. The run panics when the program asks for more inputs than were fed.
+//!
+//! ## Transcripts
+//!
+//! [`Harness::record`] writes a transcript of the run (see [`crate::transcript`])
+//! and [`Harness::replay`] answers the inputs from one, to check that a recorded
+//! session still replays against the current program.
+//!
+//! ## Limitations
+//!
+//! Only output from the thread calling [`Harness::run`] is captured; threads
+//! spawned by the program still write to the global backend.
+
+use crate::backend::{self, render_table, strip_html, terminal_value, Backend};
+use crate::gui::GuiState;
+use crate::input::{InputKind, Validator};
+use crate::output::OutputItem;
+use crate::print::process_styles;
+use crate::transcript::{RecordingBackend, ReplayBackend};
+use regex::Regex;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Queues inputs and runs a program against an in-memory state.
+#[derive(Default)]
+pub struct Harness {
+    inputs: VecDeque<String>,
+    record: Option<PathBuf>,
+    replay: Option<PathBuf>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the answer to the next input request.
+    pub fn feed(&mut self, value: impl Into<String>) -> &mut Self {
+        self.inputs.push_back(value.into());
+        self
+    }
+
+    /// Writes a transcript of each run to `path`; see [`crate::transcript`].
+    pub fn record(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.record = Some(path.into());
+        self
+    }
+
+    /// Answers inputs from the transcript at `path` instead of the fed values.
+    /// The replayed answers are shown as `prompt answer` lines, as in the terminal.
+    pub fn replay(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.replay = Some(path.into());
+        self
+    }
+
+    /// Runs `program` on the calling thread and returns its output.
+    ///
+    /// `program` may be a function marked `#[gui]`: it then runs without starting a server.
+    pub fn run<R>(&mut self, program: impl FnOnce() -> R) -> TestRun<R> {
+        let backend = Arc::new(TestBackend {
+            state: Mutex::new(GuiState::new()),
+            inputs: Mutex::new(std::mem::take(&mut self.inputs)),
+            rejections: Mutex::new(Vec::new()),
+        });
+        // Même empilement que GuiConfig : la relecture, puis l'enregistrement autour
+        let mut installed: Arc<dyn Backend> = backend.clone();
+        if let Some(path) = &self.replay {
+            installed = Arc::new(ReplayBackend::around(path, installed).unwrap_or_else(|e| panic!("{}", e)));
+        }
+        if let Some(path) = &self.record {
+            installed = Arc::new(RecordingBackend::create(path, installed).unwrap_or_else(|e| panic!("{}", e)));
+        }
+        let result = {
+            let _guard = backend::set_thread_backend(installed);
+            program()
+        };
+        let items = backend.state.lock().unwrap().output_buffer.iter().map(|entry| entry.item.clone()).collect();
+        let rejections = std::mem::take(&mut *backend.rejections.lock().unwrap());
+        let unused_inputs = backend.inputs.lock().unwrap().drain(..).collect();
+        TestRun { result, items, rejections, unused_inputs }
+    }
+}
+
+/// A fed value refused by the program's validation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rejection {
+    pub prompt: String,
+    pub value: String,
+    pub error: String,
+}
+
+/// A `<span>` produced by an `@(style)` marker.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyledSpan {
+    pub text: String,
+    /// CSS declarations, e.g. `color: #51cf66; font-weight: bold`.
+    pub style: String,
+}
+
+/// Output and result of one [`Harness::run`].
+#[derive(Debug)]
+pub struct TestRun<R> {
+    result: R,
+    items: Vec<OutputItem>,
+    rejections: Vec<Rejection>,
+    unused_inputs: Vec<String>,
+}
+
+impl<R> TestRun<R> {
+    /// Value returned by the program.
+    pub fn result(&self) -> &R {
+        &self.result
+    }
+
+    pub fn into_result(self) -> R {
+        self.result
+    }
+
+    /// Output records in display order, including input requests and responses.
+    pub fn items(&self) -> &[OutputItem] {
+        &self.items
+    }
+
+    /// Output as plain text, laid out as in the terminal backend without colors.
+    pub fn text(&self) -> String {
+        self.items.iter().map(plain_text).collect()
+    }
+
+    /// Lines of [`text`](TestRun::text).
+    pub fn lines(&self) -> Vec<String> {
+        self.text().lines().map(str::to_string).collect()
+    }
+
+    /// Every styled span of the HTML output.
+    pub fn styled_spans(&self) -> Vec<StyledSpan> {
+        lazy_static::lazy_static! {
+            static ref SPAN_REGEX: Regex = Regex::new(r#"<span style="([^"]*)">(.*?)</span>"#).unwrap();
+        }
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::Html { html } => Some(html),
+                _ => None,
+            })
+            .flat_map(|html| SPAN_REGEX.captures_iter(html))
+            .map(|caps| StyledSpan { text: strip_html(&caps[2]), style: caps[1].to_string() })
+            .collect()
+    }
+
+    /// Formulas passed to `latex`, `latex_display` and `latex_inline`.
+    pub fn latex(&self) -> Vec<&str> {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                OutputItem::Latex { formula, .. } => Some(formula.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Fed values the program refused, in order.
+    pub fn rejections(&self) -> &[Rejection] {
+        &self.rejections
+    }
+
+    /// Fed values the program never asked for.
+    pub fn unused_inputs(&self) -> &[String] {
+        &self.unused_inputs
+    }
+
+    /// Panics unless the plain text output contains `expected`.
+    pub fn assert_contains(&self, expected: &str) {
+        let text = self.text();
+        assert!(text.contains(expected), "output does not contain {:?}:\n{}", expected, text);
+    }
+
+    /// Panics unless some span containing `text` has every style of `styles`
+    /// (written as in `@(...)`, e.g. `"green, bold"`).
+    pub fn assert_styled(&self, text: &str, styles: &str) {
+        let expected = css_declarations(styles);
+        assert!(!expected.is_empty(), "no known style in {:?}", styles);
+        let spans = self.styled_spans();
+        let found = spans.iter().any(|span| {
+            let declarations: Vec<&str> = span.style.split(';').map(str::trim).collect();
+            span.text.contains(text) && expected.iter().all(|css| declarations.contains(&css.as_str()))
+        });
+        assert!(found, "no span containing {:?} styled @({}); spans: {:#?}", text, styles, spans);
+    }
+
+    /// Panics unless `formula` was rendered with `latex`, `latex_display` or `latex_inline`.
+    pub fn assert_latex(&self, formula: &str) {
+        let formulas = self.latex();
+        assert!(formulas.contains(&formula), "formula {:?} not rendered; formulas: {:?}", formula, formulas);
+    }
+}
+
+/// CSS declarations produced by an `@(...)` marker.
+fn css_declarations(styles: &str) -> Vec<String> {
+    let html = process_styles(&format!("@({})x", styles));
+    match html.split_once("style=\"").and_then(|(_, rest)| rest.split_once('"')) {
+        Some((style, _)) => style.split(';').map(|css| css.trim().to_string()).filter(|css| !css.is_empty()).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn plain_text(item: &OutputItem) -> String {
+    match item {
+        OutputItem::Text { text } => text.clone(),
+        OutputItem::Html { html } => strip_html(html),
+        OutputItem::Latex { formula, .. } => format!("{}\n", formula),
+        OutputItem::InputRequest { prompt, .. } => format!("{} ", strip_html(&process_styles(prompt))),
+        OutputItem::InputResponse { value, .. } => format!("{}\n", value),
+        OutputItem::Table(data) => render_table(data),
+        OutputItem::Plot { title, .. } => format!("[chart: {}]\n", title.as_deref().unwrap_or("untitled")),
+        OutputItem::Image { mime, caption, .. } => format!("[image: {}]\n", caption.as_deref().unwrap_or(mime)),
+        OutputItem::Error { message } => format!("❌ Error: {}\n", message),
+        OutputItem::Panic(report) => format!("💥 {}\n", report.message),
+    }
+}
+
+/// Backend of a harness run: answers from the queue, output into its own state.
+struct TestBackend {
+    state: Mutex<GuiState>,
+    inputs: Mutex<VecDeque<String>>,
+    rejections: Mutex<Vec<Rejection>>,
+}
+
+impl Backend for TestBackend {
+    fn add_output_same_line(&self, item: OutputItem) {
+        self.state.lock().unwrap().append_output(item);
+    }
+
+    fn add_output_new_line(&self, item: OutputItem) {
+        self.state.lock().unwrap().push_output(item);
+    }
+
+    fn create_input_request(&self, prompt: &str, kind: &InputKind, validator: Validator) -> String {
+        loop {
+            let typed = self.inputs.lock().unwrap().pop_front()
+                .unwrap_or_else(|| panic!("no input fed for '{}'", prompt));
+            let checked = terminal_value(kind, &typed)
+                .and_then(|value| kind.validate(&value).and_then(|()| validator(&value)).map(|()| value));
+            match checked {
+                Ok(value) => {
+                    let mut state = self.state.lock().unwrap();
+                    state.input_counter += 1;
+                    let id = format!("input_{}", state.input_counter);
+                    state.push_output(OutputItem::InputRequest {
+                        id: id.clone(),
+                        prompt: prompt.to_string(),
+                        kind: kind.clone(),
+                        history: Vec::new(),
+                    });
+                    state.push_output(OutputItem::InputResponse { id, value: kind.display_value(&value) });
+                    return value;
+                }
+                Err(error) => self.rejections.lock().unwrap().push(Rejection {
+                    prompt: prompt.to_string(),
+                    value: typed,
+                    error,
+                }),
+            }
+        }
+    }
+
+    fn output_items(&self) -> Option<Vec<OutputItem>> {
+        Some(self.state.lock().unwrap().output_buffer.iter().map(|entry| entry.item.clone()).collect())
+    }
+}
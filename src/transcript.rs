@@ -0,0 +1,263 @@
+//! # Transcript Module - Recording and Replaying Sessions
+//!
+//! A transcript is a JSON-lines file holding the output of a run and every
+//! submitted input, each with the time elapsed since the start.
+//! Replaying a transcript answers the program's inputs from the file, without
+//! a browser, which turns an interactive program into a deterministic test.
+//!
+//! ## Recording
+//!
+//! ```bash
+//! WEBRUST_RECORD=session.jsonl cargo run --example simpleio
+//! ```
+//!
+//! or, from code, `#[gui(record = "session.jsonl")]`.
+//!
+//! ## Replaying
+//!
+//! ```bash
+//! WEBRUST_REPLAY=session.jsonl cargo run --example simpleio
+//! ```
+//!
+//! Output is written to stdout as with the terminal backend. Answers are used
+//! in order; a run fails with a panic when an answer is missing or no longer
+//! accepted by the program. Both variables can be combined to record the
+//! replayed run, e.g. to compare the output of two versions of a program.
+//!
+//! ## File Format
+//!
+//! ```text
+//! {"time_ms":0,"event":"start","unix_ms":1760000000000}
+//! {"time_ms":3,"event":"output","same_line":false,"item":{"type":"html","html":"Hello<br>"}}
+//! {"time_ms":2150,"event":"input","prompt":"Your age:","value":"42"}
+//! ```
+//!
+//! `item` uses the wire format of [`OutputItem`]. Prompts and answers are
+//! not output records: they are only stored as `input` events, as submitted,
+//! except passwords. Those are written masked, as in the page,
+//!
+//! ```text
+//! {"time_ms":5120,"event":"input","prompt":"Password:","value":"••••••","masked":true}
+//! ```
+//!
+//! and a masked answer cannot be replayed. Recording with
+//! [`GuiConfig::record_secrets`](crate::gui::GuiConfig::record_secrets) or
+//! `WEBRUST_RECORD_SECRETS=1` keeps the real value, for transcripts that must
+//! replay a login; keep such files private.
+
+use crate::backend::{html_item, Backend};
+use crate::input::{InputKind, Reply, Validator};
+use crate::output::OutputItem;
+use crate::print::{process_styles, text_to_html};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Event<'a> {
+    Start { unix_ms: u128 },
+    Output { same_line: bool, item: &'a OutputItem },
+    Input {
+        prompt: &'a str,
+        value: &'a str,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        masked: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    time_ms: u128,
+    #[serde(flatten)]
+    event: Event<'a>,
+}
+
+struct Recorder {
+    file: Mutex<File>,
+    started: Instant,
+}
+
+impl Recorder {
+    fn write(&self, event: Event) {
+        let line = Line { time_ms: self.started.elapsed().as_millis(), event };
+        if let Ok(json) = serde_json::to_string(&line) {
+            let mut file = self.file.lock().unwrap();
+            // Une ligne par écriture : le fichier reste lisible si le programme s'arrête
+            let _ = writeln!(file, "{}", json);
+        }
+    }
+
+    fn input(&self, prompt: &str, value: &str, masked: bool) {
+        let value = if masked { InputKind::Password.display_value(value) } else { value.to_string() };
+        self.write(Event::Input { prompt, value: &value, masked });
+    }
+}
+
+/// Backend wrapper writing a transcript of everything passing through `inner`.
+pub struct RecordingBackend {
+    inner: Arc<dyn Backend>,
+    recorder: Arc<Recorder>,
+    secrets: bool,
+}
+
+impl RecordingBackend {
+    /// Creates (or truncates) the transcript file and records around `inner`.
+    pub fn create(path: impl AsRef<Path>, inner: Arc<dyn Backend>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let recorder = Recorder { file: Mutex::new(file), started: Instant::now() };
+        let unix_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or(0);
+        recorder.write(Event::Start { unix_ms });
+        Ok(Self { inner, recorder: Arc::new(recorder), secrets: false })
+    }
+
+    /// Writes password answers as typed instead of masked, so they can be replayed.
+    pub fn secrets(mut self, record: bool) -> Self {
+        self.secrets = record;
+        self
+    }
+
+    fn masks(&self, kind: &InputKind) -> bool {
+        matches!(kind, InputKind::Password) && !self.secrets
+    }
+
+    fn output(&self, item: &OutputItem, same_line: bool) {
+        self.recorder.write(Event::Output { same_line, item });
+    }
+}
+
+impl Backend for RecordingBackend {
+    fn add_output_same_line(&self, item: OutputItem) {
+        self.output(&item, true);
+        self.inner.add_output_same_line(item);
+    }
+
+    fn add_output_new_line(&self, item: OutputItem) {
+        self.output(&item, false);
+        self.inner.add_output_new_line(item);
+    }
+
+    fn create_input_request(&self, prompt: &str, kind: &InputKind, validator: Validator) -> String {
+        let value = self.inner.create_input_request(prompt, kind, validator);
+        self.recorder.input(prompt, &value, self.masks(kind));
+        value
+    }
+
+    fn request_input(self: Arc<Self>, prompt: String, kind: InputKind, validator: Validator, reply: Reply) {
+        let recorder = Arc::clone(&self.recorder);
+        let recorded_prompt = prompt.clone();
+        let masked = self.masks(&kind);
+        Arc::clone(&self.inner).request_input(prompt, kind, validator, Box::new(move |value| {
+            recorder.input(&recorded_prompt, &value, masked);
+            reply(value);
+        }));
+    }
+
+    // Le backend interne garde son propre rendu (ANSI pour le terminal)
+    fn print(&self, text: &str, newline: bool) {
+        self.output(&html_item(text_to_html(text), newline), !newline);
+        self.inner.print(text, newline);
+    }
+
+    fn print_html(&self, html: &str, newline: bool) {
+        self.output(&html_item(process_styles(html), newline), !newline);
+        self.inner.print_html(html, newline);
+    }
+
+    fn serves_images(&self) -> bool {
+        self.inner.serves_images()
+    }
+
+    fn output_items(&self) -> Option<Vec<OutputItem>> {
+        self.inner.output_items()
+    }
+}
+
+#[derive(Deserialize)]
+struct RecordedInput {
+    prompt: String,
+    value: String,
+    #[serde(default)]
+    masked: bool,
+}
+
+/// Backend answering inputs from a transcript; output goes to `inner`.
+pub struct ReplayBackend {
+    inner: Arc<dyn Backend>,
+    answers: Mutex<VecDeque<RecordedInput>>,
+}
+
+impl ReplayBackend {
+    /// Reads the `input` events of a transcript written by [`RecordingBackend`].
+    pub fn open<B: Backend>(path: impl AsRef<Path>, inner: B) -> Result<Self, String> {
+        Self::around(path.as_ref(), Arc::new(inner))
+    }
+
+    /// Like [`ReplayBackend::open`], for a backend already shared.
+    pub(crate) fn around(path: &Path, inner: Arc<dyn Backend>) -> Result<Self, String> {
+        let error = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+        let file = File::open(path).map_err(|e| error(&e))?;
+        let mut answers = VecDeque::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| error(&e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value: serde_json::Value = serde_json::from_str(&line)
+                .map_err(|e| error(&format!("line {}: {}", index + 1, e)))?;
+            if value.get("event").and_then(|event| event.as_str()) == Some("input") {
+                let input = serde_json::from_value(value)
+                    .map_err(|e| error(&format!("line {}: {}", index + 1, e)))?;
+                answers.push_back(input);
+            }
+        }
+        Ok(Self { inner, answers: Mutex::new(answers) })
+    }
+}
+
+impl Backend for ReplayBackend {
+    fn add_output_same_line(&self, item: OutputItem) {
+        self.inner.add_output_same_line(item);
+    }
+
+    fn add_output_new_line(&self, item: OutputItem) {
+        self.inner.add_output_new_line(item);
+    }
+
+    fn create_input_request(&self, prompt: &str, kind: &InputKind, validator: Validator) -> String {
+        let answer = self.answers.lock().unwrap().pop_front()
+            .unwrap_or_else(|| panic!("Replay transcript has no answer left for '{}'", prompt));
+        if answer.masked {
+            panic!("The answer to '{}' is a masked password; record with WEBRUST_RECORD_SECRETS=1 to replay it", prompt);
+        }
+        if answer.prompt != prompt {
+            eprintln!("⚠️ Replaying the answer to '{}' for '{}'", answer.prompt, prompt);
+        }
+        if let Err(e) = kind.validate(&answer.value).and_then(|_| validator(&answer.value)) {
+            panic!("Replayed answer '{}' for '{}' was rejected: {}", answer.value, prompt, e);
+        }
+        self.inner.print(&format!("{} {}", prompt, kind.display_value(&answer.value)), true);
+        answer.value
+    }
+
+    fn print(&self, text: &str, newline: bool) {
+        self.inner.print(text, newline);
+    }
+
+    fn print_html(&self, html: &str, newline: bool) {
+        self.inner.print_html(html, newline);
+    }
+
+    fn serves_images(&self) -> bool {
+        self.inner.serves_images()
+    }
+
+    fn output_items(&self) -> Option<Vec<OutputItem>> {
+        self.inner.output_items()
+    }
+}
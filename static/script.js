@@ -1,10 +1,34 @@
 // webrust/static/script.js
 let currentInputId = null;
 let currentInputElement = null;
-let lastOutputLength = 0;
 let processingInput = false;
 let currentErrorElement = null;
 let currentInputContainer = null;
+let currentInputControls = [];
+let outputItems = [];
+let lineVersions = [];
+let lineElements = [];
+let inputResponses = {};
+let inputRequestEntries = {};
+let lastSeq = 0;
+let pollTimer = null;
+let eventSource = null;
+let sessionClosed = false;
+// Un seul onglet répond aux saisies ; les autres regardent
+const clientId = newClientId();
+let control = 'none';
+let viewers = 1;
+
+function newClientId() {
+    if (typeof crypto !== 'undefined' && crypto.randomUUID) return crypto.randomUUID();
+    return Math.random().toString(36).slice(2) + Date.now().toString(36);
+}
+
+// `none` : personne n'a le contrôle, n'importe quel onglet peut répondre ;
+// `read_only` : onglet ouvert avec le lien de consultation du mode LAN
+function canAnswer() {
+    return control !== 'other' && control !== 'read_only';
+}
 
 function showError(message) {
     if (currentErrorElement) currentErrorElement.remove();
@@ -21,18 +45,19 @@ function clearError() {
     }
 }
 
-function processLatexLine(line) {
-    if (line.startsWith('LATEX_DISPLAY:')) {
-        const formula = line.substring(14);
-        return `<div class="latex-display">$$${formula}$$</div>`;
-    } else if (line.startsWith('LATEX_INLINE:')) {
-        const formula = line.substring(13);
-        return `<span class="latex-inline">$${formula}$</span>`;
-    }
-    return line;
-}
-
 function renderMathJax(container) {
+    // KaTeX embarqué (feature offline-math) : rendu MathML par le navigateur
+    if (typeof window !== 'undefined' && window.katex) {
+        container.querySelectorAll('[data-formula]').forEach(function (element) {
+            window.katex.render(element.dataset.formula, element, {
+                displayMode: element.classList.contains('latex-display'),
+                output: 'mathml',
+                throwOnError: false,
+                trust: false
+            });
+        });
+        return;
+    }
     // Utilisation de la notation bracket pour éviter les warnings
     if (typeof window !== 'undefined' && window.MathJax) {
         if (window.MathJax['typesetPromise']) {
@@ -47,132 +72,572 @@ function renderMathJax(container) {
     }
 }
 
-function updateDisplay() {
+// Vérifie la valeur auprès du serveur avant de l'envoyer au programme.
+function validateAndSubmit(value, field) {
     if (processingInput) return;
-    fetch('/api/state')
+    fetch('/api/validate', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ id: currentInputId, value: value, client: clientId })
+    })
         .then(response => response.json())
-        .then(data => {
-            const terminal = document.getElementById('terminal');
-            if (data.output.length !== lastOutputLength) {
-                lastOutputLength = data.output.length;
-                let currentInputValue = '';
-                if (currentInputElement && !currentInputElement.disabled) {
-                    currentInputValue = currentInputElement.value;
+        .then(result => {
+            if (result.valid) {
+                clearError();
+                submitInput(value);
+            } else {
+                showError(result.error);
+                if (field) {
+                    if (field.type === 'text' || field.type === 'password') field.value = '';
+                    field.focus();
                 }
-                terminal.innerHTML = '';
-                currentInputElement = null;
-                currentInputId = null;
-                currentErrorElement = null;
-                currentInputContainer = null;
-                let needsMathJaxRender = false;
-                for (let i = 0; i < data.output.length; i++) {
-                    const line = data.output[i];
-                    if (line.startsWith('INPUT_REQUEST:')) {
-                        const parts = line.split(':');
-                        const inputId = parts[1];
-                        const prompt = parts.slice(2).join(':');
-                        const nextLine = i + 1 < data.output.length ? data.output[i + 1] : null;
-                        const isCompleted = nextLine && !nextLine.startsWith('INPUT_REQUEST:') && !nextLine.startsWith('PROGRAM_FINISHED');
-                        if (isCompleted) {
-                            const completedDiv = document.createElement('div');
-                            completedDiv.className = 'terminal-line';
-                            completedDiv.innerHTML = `<span class="input-prompt">${prompt}</span> <span class="completed-input">${processLatexLine(nextLine)}</span>`;
-                            terminal.appendChild(completedDiv);
-                            i++;
-                        } else {
-                            currentInputId = inputId;
-                            currentInputContainer = document.createElement('div');
-                            currentInputContainer.className = 'input-container';
-                            const inputDiv = document.createElement('div');
-                            inputDiv.className = 'input-line';
-                            const promptSpan = document.createElement('span');
-                            promptSpan.className = 'input-prompt';
-                            promptSpan.innerHTML = prompt + ' ';
-                            const inputField = document.createElement('input');
-                            inputField.className = 'user-input';
-                            inputField.type = 'text';
-                            inputField.value = currentInputValue;
-                            currentInputElement = inputField;
-                            inputField.addEventListener('keypress', function(e) {
-                                if (e.key === 'Enter') {
-                                    const value = inputField.value.trim();
-                                    if (value) {
-                                        fetch('/api/validate', {
-                                            method: 'POST',
-                                            headers: { 'Content-Type': 'application/json' },
-                                            body: JSON.stringify({ id: currentInputId, value: value })
-                                        })
-                                            .then(response => response.json())
-                                            .then(result => {
-                                                if (result.valid) {
-                                                    clearError();
-                                                    submitInput();
-                                                } else {
-                                                    showError(result.error);
-                                                    inputField.value = '';
-                                                    inputField.focus();
-                                                }
-                                            });
-                                    } else {
-                                        showError("Please enter a value");
-                                        inputField.focus();
-                                    }
-                                }
-                            });
-                            inputField.addEventListener('input', function() {
-                                if (inputField.value.trim() && currentErrorElement) clearError();
-                            });
-                            inputDiv.appendChild(promptSpan);
-                            inputDiv.appendChild(inputField);
-                            currentInputContainer.appendChild(inputDiv);
-                            terminal.appendChild(currentInputContainer);
-                            setTimeout(() => {
-                                inputField.focus();
-                                inputField.setSelectionRange(inputField.value.length, inputField.value.length);
-                            }, 50);
-                        }
-                    } else if (!line.startsWith('INPUT_REQUEST:')) {
-                        const prevLine = i > 0 ? data.output[i - 1] : null;
-                        if (!prevLine || !prevLine.startsWith('INPUT_REQUEST:')) {
-                            const lineDiv = document.createElement('div');
-                            lineDiv.className = 'terminal-line';
-                            lineDiv.innerHTML = processLatexLine(line);
-                            terminal.appendChild(lineDiv);
-                            if (line.startsWith('LATEX_DISPLAY:') || line.startsWith('LATEX_INLINE:')) {
-                                needsMathJaxRender = true;
-                            }
-                        }
-                    }
-                }
-                if (needsMathJaxRender) { renderMathJax(terminal); }
-                terminal.scrollTop = terminal.scrollHeight;
             }
         });
 }
 
-function submitInput() {
-    if (currentInputId && currentInputElement && !processingInput) {
-        const value = currentInputElement.value;
-        if (value.trim()) {
-            processingInput = true;
-            currentInputElement.disabled = true;
-            fetch('/api/input', {
-                method: 'POST',
-                headers: { 'Content-Type': 'application/json' },
-                body: JSON.stringify({ id: currentInputId, value: value })
-            })
-                .then(() => {
-                    processingInput = false;
-                    currentInputId = null;
-                    currentInputElement = null;
-                    currentErrorElement = null;
-                    currentInputContainer = null;
-                    lastOutputLength = 0;
-                })
-                .catch(() => { processingInput = false; });
+function createButton(label, className, onClick) {
+    const button = document.createElement('button');
+    button.className = className;
+    button.textContent = label;
+    button.addEventListener('click', onClick);
+    return button;
+}
+
+// Champ texte (ou mot de passe) validé avec Entrée ; vide accepté s'il y a une valeur par défaut.
+function createTextField(type, kind, history) {
+    const inputField = document.createElement('input');
+    inputField.className = 'user-input';
+    inputField.type = type;
+    const hasDefault = kind && kind.default != null;
+    if (kind && kind.value != null) inputField.value = kind.value;
+    if (kind && kind.placeholder != null) inputField.placeholder = kind.placeholder;
+    else if (hasDefault) inputField.placeholder = `default: ${kind.default}`;
+    if (history && history.length) addHistoryNavigation(inputField, history);
+    inputField.addEventListener('keypress', function(e) {
+        if (e.key === 'Enter') {
+            const value = inputField.value.trim();
+            if (value || hasDefault) {
+                validateAndSubmit(type === 'password' ? inputField.value : value, inputField);
+            } else {
+                showError("Please enter a value");
+                inputField.focus();
+            }
+        }
+    });
+    inputField.addEventListener('input', function() {
+        if (inputField.value.trim() && currentErrorElement) clearError();
+    });
+    return [inputField];
+}
+
+// Flèches haut/bas : parcourt les réponses précédentes (historique gardé par le serveur).
+function addHistoryNavigation(field, history) {
+    let position = history.length;
+    let draft = '';
+    field.addEventListener('keydown', function(e) {
+        if (e.key !== 'ArrowUp' && e.key !== 'ArrowDown') return;
+        e.preventDefault();
+        if (position === history.length) draft = field.value;
+        position = e.key === 'ArrowUp' ? Math.max(0, position - 1) : Math.min(history.length, position + 1);
+        field.value = position === history.length ? draft : history[position];
+        field.setSelectionRange(field.value.length, field.value.length);
+    });
+}
+
+function createTextarea() {
+    const textarea = document.createElement('textarea');
+    textarea.className = 'user-textarea';
+    textarea.rows = 4;
+    const send = () => validateAndSubmit(textarea.value, textarea);
+    textarea.addEventListener('keydown', function(e) {
+        if (e.key === 'Enter' && (e.ctrlKey || e.metaKey)) send();
+    });
+    return [textarea, createButton('Submit', 'input-button', send)];
+}
+
+function createChoiceButtons(labels, values) {
+    return labels.map((label, index) =>
+        createButton(label, 'input-button choice-button', () => validateAndSubmit(values[index], null)));
+}
+
+function createSlider(kind) {
+    const range = document.createElement('input');
+    range.className = 'user-slider';
+    range.type = 'range';
+    range.min = kind.min;
+    range.max = kind.max;
+    range.step = kind.step;
+    range.value = kind.min;
+    const output = document.createElement('span');
+    output.className = 'slider-value';
+    output.textContent = range.value;
+    range.addEventListener('input', () => { output.textContent = range.value; });
+    const send = () => validateAndSubmit(range.value, range);
+    range.addEventListener('keypress', function(e) {
+        if (e.key === 'Enter') send();
+    });
+    return [range, output, createButton('OK', 'input-button', send)];
+}
+
+function createDateField() {
+    const field = document.createElement('input');
+    field.className = 'user-input user-date';
+    field.type = 'date';
+    const send = () => {
+        if (field.value) validateAndSubmit(field.value, field);
+        else showError("Please enter a value");
+    };
+    field.addEventListener('keypress', function(e) {
+        if (e.key === 'Enter') send();
+    });
+    return [field, createButton('OK', 'input-button', send)];
+}
+
+// Crée le widget décrit par `request.kind` ; le premier contrôle reçoit le focus.
+function createInputContainer(request) {
+    currentInputId = request.id;
+    currentErrorElement = null;
+    currentInputContainer = document.createElement('div');
+    currentInputContainer.className = 'input-container';
+    const inputDiv = document.createElement('div');
+    inputDiv.className = 'input-line';
+    const promptSpan = document.createElement('span');
+    promptSpan.className = 'input-prompt';
+    promptSpan.innerHTML = request.prompt + ' ';
+    inputDiv.appendChild(promptSpan);
+
+    const kind = request.kind || { widget: 'text' };
+    let controls;
+    switch (kind.widget) {
+        case 'password':
+            controls = createTextField('password');
+            break;
+        case 'textarea':
+            inputDiv.classList.add('input-block');
+            controls = createTextarea();
+            break;
+        case 'select':
+            controls = createChoiceButtons(kind.options, kind.options.map((_, index) => String(index)));
+            break;
+        case 'confirm':
+            controls = createChoiceButtons(['Yes', 'No'], ['true', 'false']);
+            break;
+        case 'slider':
+            controls = createSlider(kind);
+            break;
+        case 'date':
+            controls = createDateField();
+            break;
+        default:
+            controls = createTextField('text', kind, request.history);
+    }
+    controls.forEach(control => inputDiv.appendChild(control));
+    currentInputControls = controls;
+    currentInputElement = controls[0];
+    currentInputContainer.appendChild(inputDiv);
+    setTimeout(() => {
+        const field = currentInputElement;
+        if (!field) return;
+        field.focus();
+        if (field.type === 'text' || field.type === 'password') {
+            field.setSelectionRange(field.value.length, field.value.length);
+        }
+    }, 50);
+    return currentInputContainer;
+}
+
+function createLatexElement(item) {
+    const element = document.createElement(item.display ? 'div' : 'span');
+    element.className = item.display ? 'latex-display' : 'latex-inline';
+    element.textContent = item.display ? `$$${item.formula}$$` : `$${item.formula}$`;
+    element.dataset.formula = item.formula;
+    return element;
+}
+
+function createPanicBlock(report) {
+    const details = document.createElement('details');
+    details.className = 'panic-block';
+    details.open = true;
+    const summary = document.createElement('summary');
+    summary.textContent = `💥 Panic: ${report.message}`;
+    details.appendChild(summary);
+    if (report.location) {
+        const location = document.createElement('div');
+        location.className = 'panic-location';
+        location.textContent = `at ${report.location}`;
+        details.appendChild(location);
+    }
+    if (report.backtrace) {
+        const backtrace = document.createElement('pre');
+        backtrace.className = 'panic-backtrace';
+        backtrace.textContent = report.backtrace;
+        details.appendChild(backtrace);
+    }
+    return details;
+}
+
+function compareCells(a, b) {
+    if (a.number !== null && b.number !== null) {
+        return a.number - b.number;
+    }
+    if (a.number !== null) return -1;
+    if (b.number !== null) return 1;
+    return a.text.localeCompare(b.text, undefined, { numeric: true });
+}
+
+function createTable(data) {
+    const wrapper = document.createElement('div');
+    wrapper.className = 'data-table-wrapper';
+    const table = document.createElement('table');
+    table.className = 'data-table';
+    const thead = document.createElement('thead');
+    const headRow = document.createElement('tr');
+    const tbody = document.createElement('tbody');
+
+    const rows = data.rows.map(cells => {
+        const tr = document.createElement('tr');
+        const values = cells.map((cell, index) => {
+            const td = document.createElement('td');
+            td.innerHTML = cell.html;
+            td.style.textAlign = data.align[index] || 'left';
+            tr.appendChild(td);
+            return { number: cell.number, text: td.textContent };
+        });
+        return { tr, values };
+    });
+
+    let order = rows.slice();
+    let sortColumn = null;
+    let descending = false;
+    let expanded = data.max_rows === null || rows.length <= data.max_rows;
+
+    const moreButton = document.createElement('button');
+    moreButton.className = 'data-table-more';
+    moreButton.addEventListener('click', () => {
+        expanded = !expanded;
+        refresh();
+    });
+
+    function refresh() {
+        tbody.innerHTML = '';
+        const visible = expanded ? order : order.slice(0, data.max_rows);
+        visible.forEach(row => tbody.appendChild(row.tr));
+        if (data.max_rows !== null && rows.length > data.max_rows) {
+            moreButton.textContent = expanded ? 'Show less' : `Show ${rows.length - data.max_rows} more`;
+            moreButton.hidden = false;
+        } else {
+            moreButton.hidden = true;
+        }
+    }
+
+    data.headers.forEach((header, index) => {
+        const th = document.createElement('th');
+        th.innerHTML = header;
+        th.style.textAlign = data.align[index] || 'left';
+        if (data.sortable) {
+            th.classList.add('sortable');
+            th.addEventListener('click', () => {
+                descending = sortColumn === index ? !descending : false;
+                sortColumn = index;
+                order = rows.slice().sort((a, b) => compareCells(a.values[index], b.values[index]));
+                if (descending) order.reverse();
+                headRow.querySelectorAll('th').forEach(cell => cell.classList.remove('sorted-asc', 'sorted-desc'));
+                th.classList.add(descending ? 'sorted-desc' : 'sorted-asc');
+                refresh();
+            });
         }
+        headRow.appendChild(th);
+    });
+
+    thead.appendChild(headRow);
+    table.appendChild(thead);
+    table.appendChild(tbody);
+    wrapper.appendChild(table);
+    wrapper.appendChild(moreButton);
+    refresh();
+    return wrapper;
+}
+
+// Valeur affichée une fois la saisie terminée (libellé pour les choix).
+function createCompletedInput(request, value) {
+    const element = document.createElement('div');
+    element.className = 'terminal-line';
+    const promptSpan = document.createElement('span');
+    promptSpan.className = 'input-prompt';
+    promptSpan.innerHTML = request.prompt;
+    const valueSpan = document.createElement('span');
+    valueSpan.className = 'completed-input';
+    valueSpan.textContent = value;
+    element.appendChild(promptSpan);
+    element.appendChild(document.createTextNode(' '));
+    element.appendChild(valueSpan);
+    return element;
+}
+
+// Demande en attente vue par un onglet qui n'a pas le contrôle.
+function createWaitingInput(request) {
+    const element = document.createElement('div');
+    element.className = 'terminal-line';
+    const promptSpan = document.createElement('span');
+    promptSpan.className = 'input-prompt';
+    promptSpan.innerHTML = request.prompt;
+    const waiting = document.createElement('span');
+    waiting.className = 'input-waiting';
+    waiting.textContent = control === 'read_only' ? 'waiting for an answer…' : 'waiting for the controlling tab…';
+    element.appendChild(promptSpan);
+    element.appendChild(document.createTextNode(' '));
+    element.appendChild(waiting);
+    return element;
+}
+
+function forgetCurrentInput() {
+    currentInputId = null;
+    currentInputElement = null;
+    currentErrorElement = null;
+    currentInputContainer = null;
+    currentInputControls = [];
+}
+
+// Image servie à part : l'état ne contient que son identifiant
+function createImage(item) {
+    const figure = document.createElement('figure');
+    figure.className = 'image';
+    const img = document.createElement('img');
+    img.src = `/api/blob/${encodeURIComponent(item.blob)}`;
+    img.alt = item.caption || 'image';
+    if (item.width) img.width = item.width;
+    if (item.height) img.height = item.height;
+    figure.appendChild(img);
+    if (item.caption) {
+        const caption = document.createElement('figcaption');
+        caption.textContent = item.caption;
+        figure.appendChild(caption);
     }
+    return figure;
 }
 
-setInterval(updateDisplay, 300);
-updateDisplay();
\ No newline at end of file
+// Rend l'entrée `id` selon le type de son enregistrement.
+function renderEntry(id) {
+    const terminal = document.getElementById('terminal');
+    const item = outputItems[id];
+    const previous = lineElements[id];
+    let element = document.createElement('div');
+    element.className = 'terminal-line';
+    switch (item.type) {
+        case 'html':
+            element.innerHTML = item.html;
+            break;
+        case 'text':
+            element.textContent = item.text;
+            break;
+        case 'latex':
+            element.appendChild(createLatexElement(item));
+            break;
+        case 'input_request':
+            if (item.id in inputResponses) {
+                element = createCompletedInput(item, inputResponses[item.id]);
+                if (currentInputId === item.id) forgetCurrentInput();
+            } else if (!canAnswer()) {
+                if (currentInputId === item.id) forgetCurrentInput();
+                element = createWaitingInput(item);
+            } else if (previous && currentInputId === item.id) {
+                return null; // garder le champ actif et ce que l'utilisateur a déjà tapé
+            } else {
+                element = createInputContainer(item);
+            }
+            break;
+        case 'error':
+            element.className = 'program-error';
+            element.textContent = `❌ Error: ${item.message}`;
+            break;
+        case 'panic':
+            element = createPanicBlock(item);
+            break;
+        case 'table':
+            element = createTable(item);
+            break;
+        case 'plot':
+            // SVG généré côté serveur, textes échappés
+            element = document.createElement('div');
+            element.className = 'plot';
+            element.innerHTML = item.svg;
+            break;
+        case 'image':
+            element = createImage(item);
+            break;
+        default:
+            // Les réponses sont affichées avec leur demande ; types inconnus ignorés
+            element.hidden = true;
+    }
+    if (previous) terminal.replaceChild(element, previous);
+    else terminal.appendChild(element);
+    lineElements[id] = element;
+    return element;
+}
+
+function resetTerminal() {
+    document.getElementById('terminal').innerHTML = '';
+    outputItems = [];
+    lineVersions = [];
+    lineElements = [];
+    inputResponses = {};
+    inputRequestEntries = {};
+    forgetCurrentInput();
+}
+
+// Applique une liste d'entrées { id, version, item } et ne redessine que ce qui a changé.
+function applyLines(entries) {
+    const affected = new Set();
+    for (const entry of entries) {
+        if (lineVersions[entry.id] === entry.version) continue;
+        const item = entry.item;
+        outputItems[entry.id] = item;
+        lineVersions[entry.id] = entry.version;
+        affected.add(entry.id);
+        if (item.type === 'input_request') {
+            inputRequestEntries[item.id] = entry.id;
+        } else if (item.type === 'input_response') {
+            inputResponses[item.id] = item.value;
+            if (item.id in inputRequestEntries) affected.add(inputRequestEntries[item.id]);
+        }
+    }
+    if (affected.size === 0) return;
+    const mathElements = [];
+    for (const id of Array.from(affected).sort((a, b) => a - b)) {
+        const element = renderEntry(id);
+        if (element && outputItems[id].type === 'latex') mathElements.push(element);
+    }
+    for (const element of mathElements) renderMathJax(element);
+    const terminal = document.getElementById('terminal');
+    terminal.scrollTop = terminal.scrollHeight;
+}
+
+function updateStatus(programFinished, programFailed) {
+    if (sessionClosed) return;
+    let status = programFailed ? 'Program failed' : (programFinished ? 'Program finished' : 'Running…');
+    if (control === 'read_only') status += ' · view only';
+    else if (control === 'other') status += ' · observing';
+    else if (control === 'none' && viewers > 1) status += ' · no tab in control';
+    else if (viewers > 1) status += ` · in control, ${viewers} tabs open`;
+    document.getElementById('status-text').textContent = status;
+    document.getElementById('close-button').hidden = !programFinished || !canAnswer();
+    const controlButton = document.getElementById('control-button');
+    controlButton.hidden = programFinished || !(control === 'none' || (control === 'you' && viewers > 1));
+    controlButton.textContent = control === 'you' ? 'Hand over control' : 'Take control';
+}
+
+// Change le contrôle ; les demandes en attente passent du widget à l'attente (ou l'inverse).
+function setControl(newControl, newViewers) {
+    if (newViewers != null) viewers = newViewers;
+    if (newControl == null || newControl === control) return;
+    const couldAnswer = canAnswer();
+    control = newControl;
+    if (couldAnswer === canAnswer()) return;
+    for (const requestId in inputRequestEntries) {
+        if (!(requestId in inputResponses)) renderEntry(inputRequestEntries[requestId]);
+    }
+}
+
+function toggleControl() {
+    fetch('/api/control', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ client: clientId, action: control === 'you' ? 'release' : 'take' })
+    })
+        .then(response => response.json())
+        .then(result => setControl(result.control))
+        .catch(() => {});
+}
+
+function closeSession() {
+    sessionClosed = true;
+    if (eventSource) eventSource.close();
+    if (pollTimer) clearInterval(pollTimer);
+    document.getElementById('close-button').hidden = true;
+    // Le serveur s'arrête : les exports ne sont plus disponibles
+    document.getElementById('download').hidden = true;
+    document.getElementById('status-text').textContent = 'Session closed, you can close this tab';
+    fetch('/api/close', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify({ client: clientId })
+    }).catch(() => {});
+}
+
+function applyUpdate(data) {
+    if (data.reset) resetTerminal();
+    lastSeq = data.seq;
+    setControl(data.control, data.viewers);
+    applyLines(data.lines);
+    updateStatus(data.program_finished, data.program_failed);
+}
+
+function connectEvents() {
+    // EventSource se reconnecte seul et renvoie le dernier id reçu (Last-Event-ID)
+    const source = new EventSource(`/api/events?since=${lastSeq}&client=${clientId}`);
+    eventSource = source;
+    source.onmessage = function(event) {
+        applyUpdate(JSON.parse(event.data));
+    };
+    source.onerror = function() {
+        // Flux refusé (proxy, navigateur) : on passe au polling incrémental
+        if (source.readyState === EventSource.CLOSED) startPolling();
+    };
+}
+
+// Repli sans flux : seules les entrées modifiées depuis `lastSeq` sont demandées.
+function pollState() {
+    fetch(`/api/state?since=${lastSeq}&client=${clientId}`)
+        .then(response => response.json())
+        .then(applyUpdate)
+        .catch(() => {});
+}
+
+function startPolling() {
+    if (pollTimer) return;
+    pollTimer = setInterval(pollState, 300);
+    pollState();
+}
+
+function submitInput(value) {
+    if (currentInputId && !processingInput) {
+        processingInput = true;
+        currentInputControls.forEach(control => { control.disabled = true; });
+        fetch('/api/input', {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: JSON.stringify({ id: currentInputId, value: value, client: clientId })
+        })
+            .then(response => {
+                processingInput = false;
+                if (response.ok) return;
+                // Refusée par le serveur : on réactive le widget avec le message
+                currentInputControls.forEach(control => { control.disabled = false; });
+                response.json().then(result => showError(result.error)).catch(() => {});
+            })
+            .catch(() => { processingInput = false; });
+    }
+}
+
+// Le jeton est gardé dans un cookie : on l'ôte de la barre d'adresse (démo projetée)
+if (typeof history !== 'undefined' && history.replaceState && /[?&]token=/.test(location.search)) {
+    history.replaceState(null, '', location.pathname);
+}
+
+document.getElementById('close-button').addEventListener('click', closeSession);
+document.getElementById('control-button').addEventListener('click', toggleControl);
+document.getElementById('download-button').addEventListener('click', function() {
+    const menu = document.getElementById('download-menu');
+    menu.hidden = !menu.hidden;
+});
+document.getElementById('print-link').addEventListener('click', function(e) {
+    e.preventDefault();
+    document.getElementById('download-menu').hidden = true;
+    window.print();
+});
+
+if (typeof EventSource !== 'undefined') {
+    connectEvents();
+} else {
+    startPolling();
+}
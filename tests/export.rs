@@ -0,0 +1,52 @@
+use webrust::output::OutputItem;
+use webrust::prelude::*;
+use webrust::testing::Harness;
+use std::fs;
+
+#[gui]
+fn report() {
+    let name: String = input("@(blue)Name:");
+    println("Report for @(bold){name}");
+    let dir = std::env::temp_dir().join(format!("webrust-export-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    export_html(dir.join("report.html")).unwrap();
+    export_markdown(dir.join("report.md")).unwrap();
+}
+
+#[test]
+fn harness_runs_can_be_exported() {
+    Harness::new().feed("Ada").run(report);
+    let dir = std::env::temp_dir().join(format!("webrust-export-{}", std::process::id()));
+    let html = fs::read_to_string(dir.join("report.html")).unwrap();
+    let markdown = fs::read_to_string(dir.join("report.md")).unwrap();
+    let _ = fs::remove_dir_all(&dir);
+
+    assert!(html.contains("Ada"), "{}", html);
+    assert!(!html.contains("@(blue)"), "style marker left in the prompt: {}", html);
+    assert!(html.contains("<span class=\"input-prompt\"><span style=\"color: #339af0\">Name:"), "{}", html);
+    assert!(markdown.contains("**Ada**"), "{}", markdown);
+}
+
+#[test]
+fn terminal_backend_refuses_to_export() {
+    // Sans harnais ni serveur, le backend global est celui du web : on installe le terminal
+    webrust::backend::set_backend(webrust::backend::TerminalBackend::new());
+    let error = export_html(std::env::temp_dir().join("webrust-never-written.html")).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Unsupported);
+}
+
+#[test]
+fn the_page_and_the_export_style_prompts_alike() {
+    let path = std::env::temp_dir().join(format!("webrust-prompt-{}.html", std::process::id()));
+    let run = Harness::new().feed("Ada").run(|| {
+        let _: String = input("@(blue)Name <first>:");
+        export_html(&path).unwrap();
+    });
+    let html = fs::read_to_string(&path).unwrap();
+    let _ = fs::remove_file(&path);
+
+    let request = run.items().iter().find(|item| matches!(item, OutputItem::InputRequest { .. })).unwrap();
+    let sent = serde_json::to_value(request).unwrap()["prompt"].as_str().unwrap().to_string();
+    assert_eq!(sent, "<span style=\"color: #339af0\">Name &lt;first&gt;:</span>");
+    assert!(html.contains(&format!("<span class=\"input-prompt\">{}</span>", sent)), "{}", html);
+}
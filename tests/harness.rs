@@ -0,0 +1,53 @@
+use webrust::prelude::*;
+use webrust::testing::Harness;
+
+#[gui]
+fn quiz() {
+    let name: String = input("Name:");
+    let age: u32 = input("Age:");
+    println("Hello @(green, bold){name}@(reset), next year you will be {age + 1}");
+    latex_display("\\sum_{k=1}^{n} k = \\frac{n(n+1)}{2}");
+    latex_inline("x^2");
+}
+
+#[test]
+fn fed_inputs_reach_the_program() {
+    let mut harness = Harness::new();
+    harness.feed("Ada").feed("old").feed("36").feed("unused");
+    let run = harness.run(quiz);
+
+    assert!(run.text().contains("Hello Ada, next year you will be 37"), "{}", run.text());
+    assert_eq!(run.rejections().len(), 1);
+    assert_eq!(run.rejections()[0].prompt, "Age:");
+    assert_eq!(run.rejections()[0].value, "old");
+    assert_eq!(run.unused_inputs(), ["unused"]);
+}
+
+#[test]
+fn formulas_are_collected_in_order() {
+    let mut harness = Harness::new();
+    harness.feed("Ada").feed("36");
+    let run = harness.run(quiz);
+
+    assert_eq!(run.latex(), ["\\sum_{k=1}^{n} k = \\frac{n(n+1)}{2}", "x^2"]);
+    run.assert_latex("x^2");
+}
+
+#[test]
+fn styled_spans_keep_their_styles() {
+    let mut harness = Harness::new();
+    harness.feed("Ada").feed("36");
+    let run = harness.run(quiz);
+
+    let spans = run.styled_spans();
+    let name = spans.iter().find(|span| span.text == "Ada").expect("no span for the name");
+    assert!(name.style.contains("font-weight: bold"), "{}", name.style);
+    run.assert_styled("Ada", "green, bold");
+    assert!(!spans.iter().any(|span| span.text.contains("next year")), "{:?}", spans);
+}
+
+#[test]
+#[should_panic]
+fn missing_inputs_fail_the_run() {
+    Harness::new().feed("Ada").run(quiz);
+}
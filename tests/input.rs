@@ -0,0 +1,44 @@
+use webrust::input::Input;
+use webrust::testing::Harness;
+
+fn age() -> i32 {
+    Input::<i32>::new("Age:")
+        .min(0)
+        .max(150)
+        .error_msg("Please enter an age between 0 and 150")
+        .default(18)
+        .get()
+}
+
+#[test]
+fn a_rejected_value_asks_again_with_the_custom_message() {
+    let run = Harness::new().feed("200").feed("forty").feed("40").run(age);
+
+    assert_eq!(*run.result(), 40);
+    let errors: Vec<&str> = run.rejections().iter().map(|rejection| rejection.error.as_str()).collect();
+    assert_eq!(errors, ["Please enter an age between 0 and 150", "invalid digit found in string"]);
+    assert!(run.rejections().iter().all(|rejection| rejection.prompt == "Age:"));
+}
+
+#[test]
+fn an_empty_answer_takes_the_default() {
+    let run = Harness::new().feed("").run(age);
+
+    assert_eq!(*run.result(), 18);
+    assert!(run.rejections().is_empty());
+    run.assert_contains("Age: 18");
+}
+
+#[test]
+fn a_custom_check_is_applied_after_parsing() {
+    let run = Harness::new().feed("bob smith").feed("bob").run(|| {
+        Input::<String>::new("Username:")
+            .validate(|name| name.chars().all(char::is_alphanumeric))
+            .error_msg("Letters and digits only")
+            .get()
+    });
+
+    assert_eq!(run.result(), "bob");
+    assert_eq!(run.rejections()[0].value, "bob smith");
+    assert_eq!(run.rejections()[0].error, "Letters and digits only");
+}
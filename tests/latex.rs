@@ -0,0 +1,58 @@
+use webrust::output::OutputItem;
+use webrust::prelude::*;
+use webrust::testing::Harness;
+
+fn rendered(program: fn()) -> Vec<(String, bool)> {
+    Harness::new()
+        .run(program)
+        .items()
+        .iter()
+        .filter_map(|item| match item {
+            OutputItem::Latex { formula, display } => Some((formula.clone(), *display)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn delimiters_are_removed_and_select_the_mode() {
+    let formulas = rendered(|| {
+        latex("\\[ \\int_0^1 x\\,dx \\]");
+        latex("$$a^2 + b^2$$");
+        latex("\\(e^{i\\pi}\\)");
+        latex("$x_1$");
+        latex("E = mc^2");
+        latex("\\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\end{pmatrix}");
+    });
+    assert_eq!(formulas, [
+        ("\\int_0^1 x\\,dx".to_string(), true),
+        ("a^2 + b^2".to_string(), true),
+        ("e^{i\\pi}".to_string(), false),
+        ("x_1".to_string(), false),
+        ("E = mc^2".to_string(), false),
+        ("\\begin{pmatrix} 1 & 0 \\\\ 0 & 1 \\end{pmatrix}".to_string(), true),
+    ]);
+}
+
+#[test]
+fn forced_modes_win_over_delimiters() {
+    let formulas = rendered(|| {
+        latex_inline("\\[x\\]");
+        latex_display("$y$");
+    });
+    assert_eq!(formulas, [("x".to_string(), false), ("y".to_string(), true)]);
+}
+
+#[test]
+fn two_inline_formulas_on_one_line_are_joined() {
+    let formulas = rendered(|| {
+        latex("$a$ and $b$");
+        latex("Let $x_1 = 2$, then \\[x_1^2 = 4\\]");
+        latex("costs \\$5, not $n$");
+    });
+    assert_eq!(formulas, [
+        ("a\\text{ and }b".to_string(), false),
+        ("\\text{Let }x_1 = 2\\text{, then }x_1^2 = 4".to_string(), true),
+        ("\\text{costs \\$5, not }n".to_string(), false),
+    ]);
+}
@@ -0,0 +1,53 @@
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use webrust::prelude::*;
+use webrust::testing::Harness;
+
+fn transcript(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("webrust-{}-{}.jsonl", name, std::process::id()))
+}
+
+#[gui]
+fn survey() {
+    let name: String = input("@(blue)Name:");
+    let age: u32 = input("Age:");
+    println("Hello @(bold){name}, next year you will be {age + 1}");
+    latex("$x^2$");
+}
+
+#[gui]
+fn login() {
+    let user: String = input("User:");
+    let secret = password("Password:");
+    println("{user} typed {} characters", secret.len());
+}
+
+#[test]
+fn a_recorded_run_replays_with_the_same_output() {
+    let path = transcript("survey");
+    let recorded = Harness::new().record(&path).feed("Ada").feed("old").feed("36").run(survey);
+    let replayed = Harness::new().replay(&path).run(survey);
+    let _ = fs::remove_file(&path);
+
+    // Seules les réponses acceptées sont enregistrées
+    assert_eq!(recorded.rejections().len(), 1);
+    assert_eq!(replayed.text(), recorded.text());
+    assert!(replayed.text().contains("Name: Ada\nAge: 36\nHello Ada, next year you will be 37"), "{}", replayed.text());
+    assert_eq!(replayed.latex(), ["x^2"]);
+}
+
+#[test]
+fn masked_passwords_cannot_be_replayed() {
+    let path = transcript("login");
+    Harness::new().record(&path).feed("ada").feed("hunter2").run(login);
+    let recorded = fs::read_to_string(&path).unwrap();
+    let replay = panic::catch_unwind(AssertUnwindSafe(|| Harness::new().replay(&path).run(login)));
+    let _ = fs::remove_file(&path);
+
+    assert!(!recorded.contains("hunter2"), "{}", recorded);
+    assert!(recorded.contains("\"masked\":true"), "{}", recorded);
+    let payload = replay.expect_err("a masked password was replayed");
+    let message = payload.downcast_ref::<String>().cloned().unwrap_or_default();
+    assert!(message.contains("masked password"), "{}", message);
+}
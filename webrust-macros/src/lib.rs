@@ -11,7 +11,7 @@ impl VisitMut for FStringTransformer {
         if let Expr::Call(ExprCall { func, args, .. }) = expr {
             if let Expr::Path(ExprPath { path, .. }) = func.as_ref() {
                 if path.segments.len() == 1 &&
-                    (path.segments[0].ident == "println" || path.segments[0].ident == "print") {
+                    ["println", "print", "println_html", "print_html"].iter().any(|name| path.segments[0].ident == name) {
                     if let Some(Expr::Lit(ExprLit { lit: Lit::Str(lit_str), .. })) = args.first() {
                         let template = lit_str.value();
                         let transformations = extract_and_process_variables(&template);
@@ -92,15 +92,113 @@ fn extract_and_process_variables(template: &str) -> Vec<(String, String, Option<
     transformations
 }
 
+/// Options accepted by `#[gui(...)]`, turned into `GuiConfig` builder calls.
+#[derive(Default)]
+struct GuiArgs {
+    config_calls: Vec<proc_macro2::TokenStream>,
+    tls_cert: Option<syn::LitStr>,
+    tls_key: Option<syn::LitStr>,
+}
+
+impl GuiArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("port") {
+            let port: syn::LitInt = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .port(#port) });
+        } else if meta.path.is_ident("ports") {
+            let range: syn::ExprRange = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .ports(#range) });
+        } else if meta.path.is_ident("host") {
+            let host: syn::LitStr = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .host(#host) });
+        } else if meta.path.is_ident("fallback") {
+            let fallback: syn::LitBool = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .fallback(#fallback) });
+        } else if meta.path.is_ident("backend") {
+            let backend: syn::LitStr = meta.value()?.parse()?;
+            let kind = match backend.value().as_str() {
+                "web" => quote! { webrust::backend::BackendKind::Web },
+                "terminal" => quote! { webrust::backend::BackendKind::Terminal },
+                _ => return Err(syn::Error::new(backend.span(), "expected \"web\" or \"terminal\"")),
+            };
+            self.config_calls.push(quote! { .backend(#kind) });
+        } else if meta.path.is_ident("shutdown") {
+            let shutdown: syn::LitStr = meta.value()?.parse()?;
+            let policy = match shutdown.value().as_str() {
+                "tab_close" => quote! { webrust::gui::ShutdownPolicy::OnTabClose },
+                "close_button" => quote! { webrust::gui::ShutdownPolicy::OnCloseButton },
+                "never" => quote! { webrust::gui::ShutdownPolicy::Never },
+                _ => return Err(syn::Error::new(shutdown.span(), "expected \"tab_close\", \"close_button\" or \"never\"")),
+            };
+            self.config_calls.push(quote! { .shutdown(#policy) });
+        } else if meta.path.is_ident("timeout") {
+            let seconds: syn::LitInt = meta.value()?.parse()?;
+            self.config_calls.push(quote! {
+                .shutdown(webrust::gui::ShutdownPolicy::Timeout(std::time::Duration::from_secs(#seconds)))
+            });
+        } else if meta.path.is_ident("record") {
+            let path: syn::LitStr = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .record(#path) });
+        } else if meta.path.is_ident("record_secrets") {
+            let record: syn::LitBool = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .record_secrets(#record) });
+        } else if meta.path.is_ident("replay") {
+            let path: syn::LitStr = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .replay(#path) });
+        } else if meta.path.is_ident("math_url") {
+            let url: syn::LitStr = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .math_url(#url) });
+        } else if meta.path.is_ident("assets_dir") {
+            let dir: syn::LitStr = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .assets_dir(#dir) });
+        } else if meta.path.is_ident("lan") {
+            let lan: syn::LitBool = meta.value()?.parse()?;
+            self.config_calls.push(quote! { .lan(#lan) });
+        } else if meta.path.is_ident("tls_cert") {
+            self.tls_cert = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("tls_key") {
+            self.tls_key = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("unsupported gui option, expected `port`, `ports`, `host`, `fallback`, `backend`, `shutdown`, `timeout`, `record`, `record_secrets`, `replay`, `math_url`, `assets_dir`, `lan`, `tls_cert` or `tls_key`"));
+        }
+        Ok(())
+    }
+
+    /// Adds the calls needing several options, once all are parsed.
+    fn finish(&mut self) -> syn::Result<()> {
+        match (&self.tls_cert, &self.tls_key) {
+            (Some(cert), Some(key)) => self.config_calls.push(quote! { .tls(#cert, #key) }),
+            (Some(path), None) | (None, Some(path)) => {
+                return Err(syn::Error::new(path.span(), "`tls_cert` and `tls_key` must be given together"));
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
 #[proc_macro_attribute]
-pub fn gui(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn gui(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut gui_args = GuiArgs::default();
+    let args_parser = syn::meta::parser(|meta| gui_args.parse(meta));
+    parse_macro_input!(args with args_parser);
+    if let Err(e) = gui_args.finish() {
+        return e.to_compile_error().into();
+    }
+    let config_calls = &gui_args.config_calls;
     let mut input_fn = parse_macro_input!(input as ItemFn);
     FStringTransformer.visit_item_fn_mut(&mut input_fn);
     let fn_name = &input_fn.sig.ident;
     let fn_block = &input_fn.block;
+    let fn_output = &input_fn.sig.output;
+    // La fermeture garde le type de retour déclaré pour que `?` fonctionne dans le corps
+    let closure_output = match fn_output {
+        syn::ReturnType::Default => quote! {},
+        syn::ReturnType::Type(arrow, ty) => quote! { #arrow #ty },
+    };
     let expanded = quote! {
         #[allow(unused_variables)]
-        fn #fn_name() {
+        fn #fn_name() #fn_output {
             fn webrust_format_json_clean(debug_str: &str) -> String {
                 // Version simplifiée sans regex pour éviter les dépendances
                 let lines: Vec<&str> = debug_str.lines().collect();
@@ -137,9 +235,14 @@ pub fn gui(_args: TokenStream, input: TokenStream) -> TokenStream {
             }
             fn println<T: std::fmt::Display>(text: T) { webrust::print::println_str(text); }
             fn print<T: std::fmt::Display>(text: T) { webrust::print::print_str(text); }
+            #[allow(dead_code)]
+            fn println_html<T: std::fmt::Display>(html: T) { webrust::print::println_html(html); }
+            #[allow(dead_code)]
+            fn print_html<T: std::fmt::Display>(html: T) { webrust::print::print_html(html); }
             use webrust::input::input_with_validation as input;
             use webrust::latex::{latex, latex_display, latex_inline};
-            webrust::gui::start_gui_server(|| { #fn_block });
+            let webrust_config = webrust::gui::GuiConfig::new() #(#config_calls)* .apply_env();
+            webrust::gui::start_gui_server(webrust_config, || #closure_output #fn_block)
         }
     };
     TokenStream::from(expanded)